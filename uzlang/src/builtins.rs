@@ -0,0 +1,202 @@
+use crate::interpreter::{
+    DictKey, Interpreter, RuntimeError, RuntimeErrorKind, Value, parse_float,
+};
+use crate::lexer::normalize_apostrophe;
+use crate::native::NativeArgs;
+use crate::{files, math, net, strings};
+use std::rc::Rc;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+/// Tilning standart funksiyalarini ro'yxatdan o'tkazadi.
+pub(crate) fn register(interpreter: &mut Interpreter) {
+    interpreter.register_native(
+        "son",
+        1,
+        "son(qiymat): matn yoki haqiqiy sonni butun songa aylantiradi",
+        son,
+    );
+    interpreter.register_native(
+        "haqiqiy",
+        1,
+        "haqiqiy(qiymat): matn yoki butun sonni haqiqiy songa aylantiradi",
+        haqiqiy,
+    );
+    interpreter.register_native(
+        "mantiq",
+        1,
+        "mantiq(qiymat): \"rost\"/\"yolg'on\" matnini yoki sonni mantiqiy qiymatga aylantiradi",
+        mantiq,
+    );
+    interpreter.register_native(
+        "matn",
+        1,
+        "matn(qiymat): qiymatni matnga aylantiradi",
+        |_, args| Ok(Value::String(Rc::from(args.get(0).to_string()))),
+    );
+    interpreter.register_native(
+        "turi",
+        1,
+        "turi(qiymat): qiymat turining nomini qaytaradi",
+        |_, args| Ok(Value::String(Rc::from(args.get(0).type_name()))),
+    );
+    interpreter.register_native(
+        "uzunlik",
+        1,
+        "uzunlik(to'plam): massiv yoki lug'atdagi elementlar, matndagi harflar soni",
+        uzunlik,
+    );
+    interpreter.register_native(
+        "kalitlar",
+        1,
+        "kalitlar(lug'at): lug'at kalitlari massivi",
+        |_, args| {
+            let dict = args.dict(0)?;
+            Ok(Value::Array(Rc::new(
+                dict.keys().map(DictKey::to_value).collect(),
+            )))
+        },
+    );
+    interpreter.register_native(
+        "qiymatlar",
+        1,
+        "qiymatlar(lug'at): lug'at qiymatlari massivi",
+        |_, args| {
+            let dict = args.dict(0)?;
+            Ok(Value::Array(Rc::new(dict.values().cloned().collect())))
+        },
+    );
+    interpreter.register_native(
+        "bormi",
+        2,
+        "bormi(lug'at, kalit): lug'atda shu kalit bor-yo'qligi",
+        |_, args| {
+            let dict = args.dict(0)?;
+            let key = DictKey::from_value(args.get(1), args.span(1))?;
+            Ok(Value::Bool(dict.contains_key(&key)))
+        },
+    );
+    interpreter.register_native(
+        "qosh",
+        2,
+        "qosh(massiv, qiymat): oxiriga qiymat qo'shilgan yangi massiv",
+        qosh,
+    );
+    interpreter.register_native(
+        "xato_chiqar",
+        1,
+        "xato_chiqar(xabar): dasturni shu xabarli xato bilan to'xtatadi; \
+         uni 'urinib ko'r' bloki ushlashi mumkin",
+        |_, args| {
+            Err(RuntimeError::new(
+                RuntimeErrorKind::Raised(args.get(0).to_string()),
+                args.span,
+            ))
+        },
+    );
+    strings::register(interpreter);
+    math::register(interpreter);
+    net::register(interpreter);
+    files::register(interpreter);
+}
+
+fn son(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    match args.get(0) {
+        Value::String(s) => s.trim().parse().map(Value::Number).map_err(|_| {
+            RuntimeError::type_mismatch(
+                format!("'{}' ni songa aylantirib bo'lmaydi", s),
+                args.span(0),
+            )
+        }),
+        Value::Number(n) => Ok(Value::Number(*n)),
+        Value::Bool(b) => Ok(Value::Number(*b as i64)),
+        // Kasr qismi tashlab yuboriladi: son(3,7) == 3
+        Value::Float(n) => {
+            if n.is_finite() && *n >= i64::MIN as f64 && *n < i64::MAX as f64 {
+                return Ok(Value::Number(n.trunc() as i64));
+            }
+            Err(RuntimeError::type_mismatch(
+                format!("{} ni songa aylantirib bo'lmaydi", args.get(0)),
+                args.span(0),
+            ))
+        }
+        other => Err(RuntimeError::type_mismatch(
+            format!("{} ni songa aylantirib bo'lmaydi", other.type_name()),
+            args.span(0),
+        )),
+    }
+}
+
+fn haqiqiy(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    match args.get(0) {
+        Value::Float(n) => Ok(Value::Float(*n)),
+        Value::Number(n) => Ok(Value::Float(*n as f64)),
+        Value::Bool(b) => Ok(Value::Float(*b as i64 as f64)),
+        Value::String(s) => parse_float(s).map(Value::Float).ok_or_else(|| {
+            RuntimeError::type_mismatch(
+                format!("'{}' ni haqiqiy songa aylantirib bo'lmaydi", s),
+                args.span(0),
+            )
+        }),
+        other => Err(RuntimeError::type_mismatch(
+            format!(
+                "{} ni haqiqiy songa aylantirib bo'lmaydi",
+                other.type_name()
+            ),
+            args.span(0),
+        )),
+    }
+}
+
+fn mantiq(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    match args.get(0) {
+        Value::Bool(b) => Ok(Value::Bool(*b)),
+        Value::Number(n) => Ok(Value::Bool(*n != 0)),
+        Value::Float(n) => Ok(Value::Bool(*n != 0.0)),
+        Value::String(s) => {
+            let word: String = s.trim().chars().map(normalize_apostrophe).collect();
+            match word.as_str() {
+                "rost" | "рост" => Ok(Value::Bool(true)),
+                "yolg'on" | "ёлғон" => Ok(Value::Bool(false)),
+                _ => Err(RuntimeError::type_mismatch(
+                    format!(
+                        "'{}' ni mantiqiy qiymatga aylantirib bo'lmaydi, \"rost\" yoki \"yolg'on\" kutilgan",
+                        s
+                    ),
+                    args.span(0),
+                )),
+            }
+        }
+        other => Err(RuntimeError::type_mismatch(
+            format!(
+                "{} ni mantiqiy qiymatga aylantirib bo'lmaydi",
+                other.type_name()
+            ),
+            args.span(0),
+        )),
+    }
+}
+
+fn uzunlik(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let len = match args.get(0) {
+        Value::Array(arr) => arr.len(),
+        Value::Dict(dict) => dict.len(),
+        Value::String(s) => s.chars().count(),
+        other => {
+            return Err(RuntimeError::type_mismatch(
+                format!(
+                    "'uzunlik' funksiyasining 1-parametri matn, massiv yoki lug'at bo'lishi kerak, {} berildi",
+                    other.type_name()
+                ),
+                args.span(0),
+            ));
+        }
+    };
+    Ok(Value::Number(len as i64))
+}
+
+fn qosh(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let mut arr = (**args.array(0)?).clone();
+    arr.push(args.get(1).clone());
+    Ok(Value::Array(Rc::new(arr)))
+}
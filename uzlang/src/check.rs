@@ -0,0 +1,200 @@
+use crate::{CapturedOutput, Error, Interpreter};
+use std::io::Cursor;
+
+/// Kutilgan va olingan chiqish orasidagi farqning bitta qatori.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    /// Kutilgan natijada bor, dastur chiqarmagan (`line` — kutilgan fayldagi raqami)
+    Missing { line: usize, text: String },
+    /// Dastur chiqargan, kutilmagan (`line` — chiqishdagi raqami)
+    Extra { line: usize, text: String },
+}
+
+/// `uzlang tekshir` natijasi.
+#[derive(Debug)]
+pub struct CheckResult {
+    /// Dastur chiqargan matn
+    pub output: String,
+    /// Dastur xato bilan to'xtagan bo'lsa
+    pub error: Option<Error>,
+    pub diff: Vec<DiffLine>,
+}
+
+impl CheckResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.diff.is_empty()
+    }
+
+    /// Foydalanuvchiga ko'rsatiladigan hisobot.
+    pub fn render(&self, source: &str, filename: &str) -> String {
+        if self.passed() {
+            return format!("✓ {}: o'tdi\n", filename);
+        }
+        let mut out = format!("✗ {}: o'tmadi\n", filename);
+        if let Some(err) = &self.error {
+            out.push_str("Dastur xato bilan to'xtadi:\n");
+            out.push_str(&err.render(source, filename));
+        }
+        if !self.diff.is_empty() {
+            out.push_str("Chiqish kutilganidan farq qiladi (- kutilgan, + olingan):\n");
+            for line in &self.diff {
+                match line {
+                    DiffLine::Missing { line, text } => {
+                        out.push_str(&format!("- {:>3} | {}\n", line, text))
+                    }
+                    DiffLine::Extra { line, text } => {
+                        out.push_str(&format!("+ {:>3} | {}\n", line, text))
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Dasturni `input` kirishi bilan bajaradi va chiqishini `expected` bilan solishtiradi.
+///
+/// Qator oxiridagi bo'shliqlar va oxirgi bo'sh qatorlar hisobga olinmaydi,
+/// qolgan hamma narsa aynan mos kelishi kerak.
+pub fn check(
+    interpreter: &mut Interpreter,
+    source: &str,
+    input: &str,
+    expected: &str,
+) -> CheckResult {
+    let output = CapturedOutput::new();
+    interpreter.set_output(output.clone());
+    interpreter.set_input(Cursor::new(input.to_string()));
+    let error = interpreter.run_source(source).err();
+    let output = output.contents();
+    let diff = diff(&lines(expected), &lines(&output));
+    CheckResult {
+        output,
+        error,
+        diff,
+    }
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines
+}
+
+/// Eng uzun umumiy qism ketma-ketligi bo'yicha qatorma-qator farq.
+fn diff(expected: &[&str], actual: &[&str]) -> Vec<DiffLine> {
+    let (n, m) = (expected.len(), actual.len());
+    // common[i][j] = LCS length of expected[i..] and actual[j..]
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && common[i + 1][j] >= common[i][j + 1]) {
+            result.push(DiffLine::Missing {
+                line: i + 1,
+                text: expected[i].to_string(),
+            });
+            i += 1;
+        } else {
+            result.push(DiffLine::Extra {
+                line: j + 1,
+                text: actual[j].to_string(),
+            });
+            j += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_support::both_engines;
+
+    fn check_both(source: &str, input: &str, expected: &str) -> CheckResult {
+        let [tree, vm] = both_engines(|_| {})
+            .map(|mut interpreter| check(&mut interpreter, source, input, expected));
+        assert_eq!(tree.output, vm.output);
+        assert_eq!(tree.diff, vm.diff);
+        assert_eq!(tree.error, vm.error);
+        vm
+    }
+
+    const SUM: &str = "a = son(so'ra)\nb = son(so'ra)\nyoz a + b\nyoz \"tayyor\"";
+
+    #[test]
+    fn test_matching_output_passes() {
+        let result = check_both(SUM, "2\n3\n", "5\ntayyor\n");
+        assert!(result.passed());
+        assert_eq!(result.output, "5\ntayyor\n");
+        // Trailing spaces and blank lines are ignored
+        assert!(check_both(SUM, "2\n3", "5  \r\ntayyor\n\n\n").passed());
+        assert_eq!(result.render(SUM, "yig.uz"), "✓ yig.uz: o'tdi\n");
+    }
+
+    #[test]
+    fn test_mismatch_reports_diff() {
+        let result = check_both(SUM, "2\n2\n", "5\ntayyor\n");
+        assert!(!result.passed());
+        assert_eq!(
+            result.diff,
+            vec![
+                DiffLine::Missing {
+                    line: 1,
+                    text: "5".to_string()
+                },
+                DiffLine::Extra {
+                    line: 1,
+                    text: "4".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            result.render(SUM, "yig.uz"),
+            "✗ yig.uz: o'tmadi\n\
+             Chiqish kutilganidan farq qiladi (- kutilgan, + olingan):\n\
+             -   1 | 5\n\
+             +   1 | 4\n"
+        );
+
+        let result = check_both("yoz 1\nyoz 3\nyoz 4", "", "1\n2\n3\n");
+        assert_eq!(
+            result.diff,
+            vec![
+                DiffLine::Missing {
+                    line: 2,
+                    text: "2".to_string()
+                },
+                DiffLine::Extra {
+                    line: 3,
+                    text: "4".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_runtime_error_fails() {
+        let source = "yoz 1\nyoz yoq";
+        let result = check_both(source, "", "1\n");
+        assert!(result.diff.is_empty());
+        assert!(!result.passed());
+        let report = result.render(source, "x.uz");
+        assert!(report.starts_with("✗ x.uz: o'tmadi\nDastur xato bilan to'xtadi:\nxato[R001]"));
+    }
+}
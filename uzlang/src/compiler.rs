@@ -0,0 +1,619 @@
+use crate::diagnostic::Span;
+use crate::interpreter::Value;
+use crate::parser::{BinOp, Expr, ExprKind, FunctionDecl, Stmt, StmtKind, UnOp};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Stek mashinasi buyrug'i.
+///
+/// `u32` maydonlar `Chunk` jadvallariga indeks yoki sakrash manzili. Mahalliy
+/// o'zgaruvchilar kompilyatsiya vaqtida slotlarga aylantiriladi; slot hali
+/// aniqlanmagan bo'lsa, o'qish va yozish global o'zgaruvchiga tushadi — bu
+/// daraxt bo'ylab yuruvchi dvigateldagi doiralar tartibi bilan bir xil.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Const(u32),
+    LoadLocal(u32),
+    LoadGlobal(u32),
+    StoreLocal(u32),
+    StoreGlobal(u32),
+    /// `funksiya` e'loni: har doim eng ichki doiraga yoziladi
+    DefineLocal(u32),
+    LoadCalleeLocal(u32),
+    LoadCalleeGlobal(u32),
+    SetIndexLocal {
+        slot: u32,
+        index_span: u32,
+    },
+    SetIndexGlobal {
+        name: u32,
+        index_span: u32,
+    },
+    Index {
+        target_span: u32,
+    },
+    Array(u32),
+    CheckKey,
+    Dict(u32),
+    Binary(BinOp),
+    Not,
+    And(u32),
+    Or(u32),
+    ToBool,
+    Jump(u32),
+    JumpIfFalse(u32),
+    Print,
+    Input,
+    Pop,
+    MakeFunction {
+        proto: u32,
+        capture: bool,
+    },
+    /// Chaqirilayotgan qiymat argumentlardan oldin stekka qo'yilgan.
+    /// `arg_spans` — argumentlar joylari `extra_spans` da shu indeksdan boshlanadi.
+    Call {
+        argc: u32,
+        callee_span: u32,
+        arg_spans: u32,
+    },
+    /// Chaqirilayotgan qiymat argumentlardan keyin, nomi bo'yicha topilgan
+    CallNamed {
+        argc: u32,
+        callee_span: u32,
+        arg_spans: u32,
+    },
+    ForStart(u32),
+    ForNext {
+        slot: u32,
+        exit: u32,
+    },
+    EndIteration,
+    ForEnd,
+    /// `ulash`: fayl yo'li va ixtiyoriy nom `names` jadvalida
+    Import {
+        path: u32,
+        alias: Option<u32>,
+    },
+    Return,
+    /// Buyruq yoki `takrorla` aylanishi boshlandi; qadamlar cheklovi uchun
+    Step,
+    /// `urinib ko'r` bloki boshlandi: xato bo'lsa, xato qiymati stekka qo'yilib,
+    /// berilgan manzilga o'tiladi
+    TryStart(u32),
+    /// `urinib ko'r` bloki xatosiz tugadi yoki undan `to'xta`/`davom et` bilan chiqildi
+    TryEnd,
+}
+
+/// Bitta dastur yoki funksiya tanasining bytecode'i.
+#[derive(Debug, Default)]
+pub struct Chunk {
+    pub code: Vec<Op>,
+    /// Har bir buyruqqa mos manba joyi (xato xabarlari uchun)
+    pub spans: Vec<Span>,
+    pub constants: Vec<Value>,
+    pub names: Vec<Rc<str>>,
+    pub functions: Vec<Rc<Proto>>,
+    /// Buyruqlarga kerak bo'ladigan qo'shimcha joylar (argumentlar, indekslar)
+    pub extra_spans: Vec<Span>,
+    /// Slot raqami bo'yicha mahalliy o'zgaruvchi nomi
+    pub slot_names: Vec<Rc<str>>,
+}
+
+/// Kompilyatsiya qilingan funksiya.
+#[derive(Debug)]
+pub struct Proto {
+    pub name: Option<Rc<str>>,
+    pub decl: Rc<FunctionDecl>,
+    /// Har bir parametr yoziladigan slot
+    pub params: Vec<u32>,
+    /// Funksiya o'z nomini ishlatsa, o'sha nom sloti
+    pub self_slot: Option<u32>,
+    pub chunk: Chunk,
+}
+
+/// Dasturning yuqori darajasini kompilyatsiya qiladi.
+pub fn compile_program(stmts: &[Stmt]) -> Chunk {
+    let mut compiler = Compiler::new(true);
+    compiler.block(stmts);
+    compiler.chunk
+}
+
+/// Funksiya e'lonini kompilyatsiya qiladi.
+pub fn compile_function(name: Option<&str>, decl: &Rc<FunctionDecl>) -> Proto {
+    let mut compiler = Compiler::new(false);
+    let params = decl.params.iter().map(|p| compiler.slot(p)).collect();
+    compiler.block(&decl.body);
+    let self_slot = name.and_then(|n| compiler.slots.get(n).copied());
+    Proto {
+        name: name.map(Rc::from),
+        decl: Rc::clone(decl),
+        params,
+        self_slot,
+        chunk: compiler.chunk,
+    }
+}
+
+struct LoopLabels {
+    // Known up front for `takrorla`; `uchun` patches its continues at the iteration end
+    continue_target: Option<usize>,
+    continues: Vec<usize>,
+    breaks: Vec<usize>,
+    // `urinib ko'r` blocks already open when the loop started
+    try_depth: usize,
+}
+
+struct Compiler {
+    chunk: Chunk,
+    slots: HashMap<Rc<str>, u32>,
+    names: HashMap<Rc<str>, u32>,
+    top_level: bool,
+    for_depth: usize,
+    try_depth: usize,
+    loops: Vec<LoopLabels>,
+}
+
+impl Compiler {
+    fn new(top_level: bool) -> Self {
+        Compiler {
+            chunk: Chunk::default(),
+            slots: HashMap::new(),
+            names: HashMap::new(),
+            top_level,
+            for_depth: 0,
+            try_depth: 0,
+            loops: Vec::new(),
+        }
+    }
+
+    /// Yuqori darajada, `uchun` tsiklidan tashqarida barcha nomlar global.
+    fn is_local_scope(&self) -> bool {
+        !self.top_level || self.for_depth > 0
+    }
+
+    fn slot(&mut self, name: &str) -> u32 {
+        if let Some(&slot) = self.slots.get(name) {
+            return slot;
+        }
+        let name: Rc<str> = Rc::from(name);
+        let slot = self.chunk.slot_names.len() as u32;
+        self.chunk.slot_names.push(name.clone());
+        self.slots.insert(name, slot);
+        slot
+    }
+
+    fn name(&mut self, name: &str) -> u32 {
+        if let Some(&idx) = self.names.get(name) {
+            return idx;
+        }
+        let name: Rc<str> = Rc::from(name);
+        let idx = self.chunk.names.len() as u32;
+        self.chunk.names.push(name.clone());
+        self.names.insert(name, idx);
+        idx
+    }
+
+    fn constant(&mut self, value: Value) -> u32 {
+        self.chunk.constants.push(value);
+        (self.chunk.constants.len() - 1) as u32
+    }
+
+    fn extra_span(&mut self, span: Span) -> u32 {
+        self.chunk.extra_spans.push(span);
+        (self.chunk.extra_spans.len() - 1) as u32
+    }
+
+    /// Argumentlar joylarini ketma-ket yozadi va birinchisining indeksini qaytaradi.
+    fn arg_spans(&mut self, args: &[Expr]) -> u32 {
+        let start = self.chunk.extra_spans.len() as u32;
+        self.chunk.extra_spans.extend(args.iter().map(|a| a.span));
+        start
+    }
+
+    fn emit(&mut self, op: Op, span: Span) -> usize {
+        self.chunk.code.push(op);
+        self.chunk.spans.push(span);
+        self.chunk.code.len() - 1
+    }
+
+    fn here(&self) -> u32 {
+        self.chunk.code.len() as u32
+    }
+
+    /// Oldin chiqarilgan sakrash buyrug'ini joriy manzilga yo'naltiradi.
+    fn patch(&mut self, at: usize) {
+        let target = self.here();
+        match &mut self.chunk.code[at] {
+            Op::Jump(t) | Op::JumpIfFalse(t) | Op::And(t) | Op::Or(t) | Op::TryStart(t) => {
+                *t = target
+            }
+            Op::ForNext { exit, .. } => *exit = target,
+            op => unreachable!("{:?} is not a jump", op),
+        }
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        let span = stmt.span;
+        self.emit(Op::Step, span);
+        match &stmt.kind {
+            StmtKind::Print(expr) => {
+                self.expr(expr);
+                self.emit(Op::Print, span);
+            }
+            StmtKind::If(branches, else_body) => {
+                let mut ends = Vec::with_capacity(branches.len());
+                for (cond, body) in branches {
+                    self.expr(cond);
+                    let skip = self.emit(Op::JumpIfFalse(0), cond.span);
+                    self.block(body);
+                    ends.push(self.emit(Op::Jump(0), span));
+                    self.patch(skip);
+                }
+                if let Some(body) = else_body {
+                    self.block(body);
+                }
+                for end in ends {
+                    self.patch(end);
+                }
+            }
+            StmtKind::Loop(cond, body) => {
+                let start = self.here() as usize;
+                self.expr(cond);
+                let exit = self.emit(Op::JumpIfFalse(0), cond.span);
+                self.emit(Op::Step, span);
+                self.loops.push(LoopLabels {
+                    continue_target: Some(start),
+                    continues: Vec::new(),
+                    breaks: Vec::new(),
+                    try_depth: self.try_depth,
+                });
+                self.block(body);
+                self.emit(Op::Jump(start as u32), span);
+                let labels = self.loops.pop().expect("loop labels");
+                self.patch(exit);
+                for at in labels.breaks {
+                    self.patch(at);
+                }
+            }
+            StmtKind::For(var_name, collection, body) => {
+                self.expr(collection);
+                // The loop variable is always a slot: it lives in the loop's own scope
+                let slot = self.slot(var_name);
+                self.emit(Op::ForStart(slot), collection.span);
+                self.for_depth += 1;
+                self.loops.push(LoopLabels {
+                    continue_target: None,
+                    continues: Vec::new(),
+                    breaks: Vec::new(),
+                    try_depth: self.try_depth,
+                });
+
+                let next = self.here();
+                let next_op = self.emit(Op::ForNext { slot, exit: 0 }, span);
+                self.block(body);
+                let labels = self.loops.pop().expect("loop labels");
+                for at in labels.continues {
+                    self.patch(at);
+                }
+                self.emit(Op::EndIteration, span);
+                self.emit(Op::Jump(next), span);
+                for at in &labels.breaks {
+                    self.patch(*at);
+                }
+                if !labels.breaks.is_empty() {
+                    self.emit(Op::EndIteration, span);
+                }
+                self.patch(next_op);
+                self.emit(Op::ForEnd, span);
+                self.for_depth -= 1;
+            }
+            StmtKind::Assign(name, expr) => {
+                self.expr(expr);
+                self.store(name, span);
+            }
+            StmtKind::AssignIndex(name, index, value) => {
+                self.expr(index);
+                self.expr(value);
+                let index_span = self.extra_span(index.span);
+                if self.is_local_scope() {
+                    let slot = self.slot(name);
+                    self.emit(Op::SetIndexLocal { slot, index_span }, span);
+                } else {
+                    let name = self.name(name);
+                    self.emit(Op::SetIndexGlobal { name, index_span }, span);
+                }
+            }
+            StmtKind::Function(name, decl) => {
+                self.function(Some(name), decl, span);
+                if self.is_local_scope() {
+                    let slot = self.slot(name);
+                    self.emit(Op::DefineLocal(slot), span);
+                } else {
+                    let name = self.name(name);
+                    self.emit(Op::StoreGlobal(name), span);
+                }
+            }
+            StmtKind::Return(expr) => {
+                self.expr(expr);
+                self.emit(Op::Return, span);
+            }
+            StmtKind::Break => {
+                self.leave_tries(span);
+                let at = self.emit(Op::Jump(0), span);
+                if let Some(labels) = self.loops.last_mut() {
+                    labels.breaks.push(at);
+                }
+            }
+            StmtKind::Continue => {
+                self.leave_tries(span);
+                let at = self.emit(Op::Jump(0), span);
+                if let Some(labels) = self.loops.last_mut() {
+                    match labels.continue_target {
+                        Some(target) => self.chunk.code[at] = Op::Jump(target as u32),
+                        None => labels.continues.push(at),
+                    }
+                }
+            }
+            StmtKind::Import(path, alias) => {
+                let path = self.name(path);
+                let alias = alias.as_deref().map(|alias| self.name(alias));
+                self.emit(Op::Import { path, alias }, span);
+            }
+            StmtKind::Try(body, name, handler) => {
+                let start = self.emit(Op::TryStart(0), span);
+                self.try_depth += 1;
+                self.block(body);
+                self.try_depth -= 1;
+                self.emit(Op::TryEnd, span);
+                let end = self.emit(Op::Jump(0), span);
+                self.patch(start);
+                // The VM pushes the error value before jumping here
+                match name {
+                    Some(name) => self.store(name, span),
+                    None => {
+                        self.emit(Op::Pop, span);
+                    }
+                }
+                self.block(handler);
+                self.patch(end);
+            }
+            StmtKind::Expr(expr) => {
+                self.expr(expr);
+                self.emit(Op::Pop, span);
+            }
+        }
+    }
+
+    /// `to'xta`/`davom et` tsikl ichida ochilgan `urinib ko'r` bloklaridan chiqadi.
+    fn leave_tries(&mut self, span: Span) {
+        let opened = self
+            .loops
+            .last()
+            .map_or(0, |labels| self.try_depth - labels.try_depth);
+        for _ in 0..opened {
+            self.emit(Op::TryEnd, span);
+        }
+    }
+
+    /// Stek ustidagi qiymatni o'zgaruvchiga yozadi.
+    fn store(&mut self, name: &str, span: Span) {
+        if self.is_local_scope() {
+            let slot = self.slot(name);
+            self.emit(Op::StoreLocal(slot), span);
+        } else {
+            let name = self.name(name);
+            self.emit(Op::StoreGlobal(name), span);
+        }
+    }
+
+    fn function(&mut self, name: Option<&str>, decl: &Rc<FunctionDecl>, span: Span) {
+        let proto = compile_function(name, decl);
+        let capture = self.is_local_scope();
+        if capture {
+            // Names used by the nested function must have slots here to be captured
+            for name in &proto.chunk.slot_names {
+                self.slot(name);
+            }
+        }
+        self.chunk.functions.push(Rc::new(proto));
+        let proto = (self.chunk.functions.len() - 1) as u32;
+        self.emit(Op::MakeFunction { proto, capture }, span);
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        let span = expr.span;
+        match &expr.kind {
+            ExprKind::Number(n) => {
+                let idx = self.constant(Value::Number(*n));
+                self.emit(Op::Const(idx), span);
+            }
+            ExprKind::Float(n) => {
+                let idx = self.constant(Value::Float(*n));
+                self.emit(Op::Const(idx), span);
+            }
+            ExprKind::StringLiteral(s) => {
+                let idx = self.constant(Value::String(Rc::from(s.as_str())));
+                self.emit(Op::Const(idx), span);
+            }
+            ExprKind::Bool(b) => {
+                let idx = self.constant(Value::Bool(*b));
+                self.emit(Op::Const(idx), span);
+            }
+            ExprKind::Identifier(name) => {
+                if self.is_local_scope() {
+                    let slot = self.slot(name);
+                    self.emit(Op::LoadLocal(slot), span);
+                } else {
+                    let name = self.name(name);
+                    self.emit(Op::LoadGlobal(name), span);
+                }
+            }
+            ExprKind::Input => {
+                self.emit(Op::Input, span);
+            }
+            ExprKind::Array(elements) => {
+                for element in elements {
+                    self.expr(element);
+                }
+                self.emit(Op::Array(elements.len() as u32), span);
+            }
+            ExprKind::Dict(entries) => {
+                for (key, value) in entries {
+                    self.expr(key);
+                    self.emit(Op::CheckKey, key.span);
+                    self.expr(value);
+                }
+                self.emit(Op::Dict(entries.len() as u32), span);
+            }
+            ExprKind::Index(target, index) => {
+                self.expr(target);
+                self.expr(index);
+                let target_span = self.extra_span(target.span);
+                self.emit(Op::Index { target_span }, index.span);
+            }
+            ExprKind::Function(decl) => self.function(None, decl, span),
+            ExprKind::Call(callee, args) => {
+                let argc = args.len() as u32;
+                match &callee.kind {
+                    ExprKind::Identifier(name) => {
+                        // Arguments are evaluated before the name is looked up
+                        for arg in args {
+                            self.expr(arg);
+                        }
+                        if self.is_local_scope() {
+                            let slot = self.slot(name);
+                            self.emit(Op::LoadCalleeLocal(slot), callee.span);
+                        } else {
+                            let name = self.name(name);
+                            self.emit(Op::LoadCalleeGlobal(name), callee.span);
+                        }
+                        let callee_span = self.extra_span(callee.span);
+                        let arg_spans = self.arg_spans(args);
+                        self.emit(
+                            Op::CallNamed {
+                                argc,
+                                callee_span,
+                                arg_spans,
+                            },
+                            span,
+                        );
+                    }
+                    _ => {
+                        self.expr(callee);
+                        for arg in args {
+                            self.expr(arg);
+                        }
+                        let callee_span = self.extra_span(callee.span);
+                        let arg_spans = self.arg_spans(args);
+                        self.emit(
+                            Op::Call {
+                                argc,
+                                callee_span,
+                                arg_spans,
+                            },
+                            span,
+                        );
+                    }
+                }
+            }
+            ExprKind::UnaryOp(UnOp::Not, operand) => {
+                self.expr(operand);
+                self.emit(Op::Not, span);
+            }
+            ExprKind::BinaryOp(left, op @ (BinOp::And | BinOp::Or), right) => {
+                self.expr(left);
+                let jump = if *op == BinOp::And {
+                    Op::And(0)
+                } else {
+                    Op::Or(0)
+                };
+                let end = self.emit(jump, span);
+                self.expr(right);
+                self.emit(Op::ToBool, span);
+                self.patch(end);
+            }
+            ExprKind::BinaryOp(left, op, right) => {
+                self.expr(left);
+                self.expr(right);
+                self.emit(Op::Binary(*op), span);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn compile(code: &str) -> Chunk {
+        let tokens = Lexer::new(code).tokenize();
+        compile_program(&Parser::new(tokens).parse())
+    }
+
+    #[test]
+    fn test_top_level_names_are_global() {
+        let chunk = compile("x = 1\ny = x + 2");
+        assert!(chunk.slot_names.is_empty());
+        assert_eq!(
+            chunk.code,
+            vec![
+                Op::Step,
+                Op::Const(0),
+                Op::StoreGlobal(0),
+                Op::Step,
+                Op::LoadGlobal(0),
+                Op::Const(1),
+                Op::Binary(BinOp::Add),
+                Op::StoreGlobal(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_locals_use_slots() {
+        let chunk = compile("funksiya f(a, b) { c = a * b\n qaytar c }");
+        let proto = &chunk.functions[0];
+        assert_eq!(proto.params, vec![0, 1]);
+        assert_eq!(
+            proto.chunk.code,
+            vec![
+                Op::Step,
+                Op::LoadLocal(0),
+                Op::LoadLocal(1),
+                Op::Binary(BinOp::Mul),
+                Op::StoreLocal(2),
+                Op::Step,
+                Op::LoadLocal(2),
+                Op::Return,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_closure_names_get_parent_slots() {
+        let chunk = compile("funksiya f() { qaytar funksiya() { qaytar n } }");
+        let outer = &chunk.functions[0];
+        assert_eq!(outer.chunk.slot_names, vec![Rc::from("n")]);
+        assert!(matches!(
+            outer.chunk.code[1],
+            Op::MakeFunction { capture: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_break_jumps_past_loop() {
+        let chunk = compile("takrorla 1 { agar 1 { to'xta } }");
+        let end = chunk.code.len() as u32;
+        assert_eq!(chunk.code[2], Op::JumpIfFalse(end));
+        assert_eq!(chunk.code[3], Op::Step);
+        assert_eq!(chunk.code[8], Op::Jump(end));
+        assert_eq!(chunk.code[10], Op::Jump(1));
+    }
+}
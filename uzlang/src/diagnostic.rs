@@ -0,0 +1,154 @@
+use std::fmt;
+
+/// Manba kodidagi joy: qator va ustun 1 dan boshlanadi, `len` belgilar soni.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, len: usize) -> Self {
+        Span { line, column, len }
+    }
+
+    /// Ikki joyni birlashtiradi. Turli qatorlarda bo'lsa, boshlang'ich joy qoladi.
+    pub fn to(self, end: Span) -> Span {
+        if end.line == self.line && end.column >= self.column {
+            Span::new(self.line, self.column, end.column + end.len - self.column)
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "xato"),
+            Severity::Warning => write!(f, "ogohlantirish"),
+        }
+    }
+}
+
+/// Lexer, parser va interpreter uchun umumiy xabar turi.
+///
+/// Kodlar bosqichga qarab prefiks oladi: `L` — lexer, `P` — parser, `R` — ijro.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            code,
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Xabarni xatoli qator va uning ostidagi `^^^` belgisi bilan chiqaradi.
+    pub fn render(&self, source: &str, filename: &str) -> String {
+        let mut out = format!("{}[{}]: {}\n", self.severity, self.code, self.message);
+        let line_no = self.span.line;
+        let gutter = line_no.to_string().len();
+        out.push_str(&format!(
+            "{:width$}--> {}:{}:{}\n",
+            "",
+            filename,
+            line_no,
+            self.span.column,
+            width = gutter
+        ));
+
+        let Some(line) = source.lines().nth(line_no.saturating_sub(1)) else {
+            return out;
+        };
+        let line = line.trim_end_matches('\r');
+
+        // Tab belgilarini saqlaymiz, aks holda ^ belgisi siljib ketadi
+        let padding: String = line
+            .chars()
+            .take(self.span.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let available = line
+            .chars()
+            .count()
+            .saturating_sub(self.span.column.saturating_sub(1));
+        let carets = self.span.len.min(available).max(1);
+
+        out.push_str(&format!("{:width$} |\n", "", width = gutter));
+        out.push_str(&format!("{} | {}\n", line_no, line));
+        out.push_str(&format!(
+            "{:width$} | {}{}\n",
+            "",
+            padding,
+            "^".repeat(carets),
+            width = gutter
+        ));
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}:{}: {}",
+            self.severity, self.code, self.span.line, self.span.column, self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_points_at_column() {
+        let source = "yoz 1\nyoz 2 +\n";
+        let diag = Diagnostic::error("P001", "Ifoda kutilgan", Span::new(2, 7, 1));
+        assert_eq!(
+            diag.render(source, "dastur.uz"),
+            "xato[P001]: Ifoda kutilgan\n --> dastur.uz:2:7\n  |\n2 | yoz 2 +\n  |       ^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_underlines_whole_span() {
+        let source = "\tx = noma'lum";
+        let diag = Diagnostic::error("R001", "O'zgaruvchi topilmadi", Span::new(1, 6, 8));
+        let rendered = diag.render(source, "a.uz");
+        assert!(rendered.ends_with("  | \t    ^^^^^^^^\n"));
+    }
+
+    #[test]
+    fn test_span_to() {
+        let a = Span::new(1, 3, 1);
+        let b = Span::new(1, 7, 2);
+        assert_eq!(a.to(b), Span::new(1, 3, 6));
+        assert_eq!(a.to(Span::new(2, 1, 1)), a);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::diagnostic::Span;
+use crate::interpreter::{Interpreter, RuntimeError, RuntimeErrorKind, Value};
+use crate::native::NativeArgs;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+/// Skriptlar fayllarni faqat shu papka ichida o'qiy va yoza oladi.
+///
+/// `create_safe_client` tarmoqda qilgani kabi, yo'l avval haqiqiy manzilga aylantiriladi
+/// (`..` va ramziy havolalar ochiladi), so'ng ildiz ichidaligi tekshiriladi.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    root: PathBuf,
+    // Set by `--ildiz` or the host; otherwise the main program's folder may replace it
+    explicit: bool,
+}
+
+impl Sandbox {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Sandbox {
+            root: root.into(),
+            explicit: true,
+        }
+    }
+
+    /// Skript bergan yo'lni ildiz ichidagi haqiqiy yo'lga aylantiradi.
+    /// Fayl hali mavjud bo'lmasligi mumkin (yozish uchun).
+    pub(crate) fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        self.resolve_in(Path::new(""), path)
+    }
+
+    /// `resolve` kabi, lekin nisbiy yo'l `dir` papkasidan boshlanadi.
+    /// `dir` ning o'zi nisbiy bo'lsa, ildizga nisbatan olinadi.
+    pub(crate) fn resolve_in(&self, dir: &Path, path: &str) -> Result<PathBuf, String> {
+        let root = fs::canonicalize(&self.root).map_err(|e| {
+            format!(
+                "Ildiz papkani ochib bo'lmadi ({}): {}",
+                self.root.display(),
+                e
+            )
+        })?;
+
+        // Absolute paths replace the root here and are checked like any other path
+        let mut normal = PathBuf::new();
+        for component in root.join(dir).join(path).components() {
+            match component {
+                Component::ParentDir => {
+                    normal.pop();
+                }
+                Component::CurDir => {}
+                other => normal.push(other),
+            }
+        }
+
+        // Resolve symlinks in the part that exists; the rest will be created as plain entries
+        let mut existing = normal.as_path();
+        let mut missing = Vec::new();
+        while fs::symlink_metadata(existing).is_err() {
+            let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                break;
+            };
+            missing.push(name);
+            existing = parent;
+        }
+        let mut real = fs::canonicalize(existing)
+            .map_err(|e| format!("'{}' yo'lini ochib bo'lmadi: {}", path, e))?;
+        real.extend(missing.iter().rev());
+
+        if !real.starts_with(&root) {
+            return Err(format!(
+                "'{}' ildiz papkadan ({}) tashqarida, unga ruxsat yo'q",
+                path,
+                root.display()
+            ));
+        }
+        Ok(real)
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Sandbox {
+            root: PathBuf::from("."),
+            explicit: false,
+        }
+    }
+}
+
+fn file_error(message: String, span: Span) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::File(message), span)
+}
+
+impl Interpreter {
+    /// Fayl funksiyalari va `ulash` ishlaydigan ildiz papkani belgilaydi.
+    /// Standart: asosiy dastur papkasi (`set_main_path`), u bo'lmasa joriy papka.
+    pub fn set_file_root(&mut self, root: impl Into<PathBuf>) {
+        self.sandbox = Sandbox::new(root);
+    }
+
+    /// Ildiz aniq belgilanmagan bo'lsa, uni asosiy dastur papkasiga o'rnatadi.
+    pub(crate) fn default_file_root(&mut self, root: &Path) {
+        if !self.sandbox.explicit {
+            self.sandbox.root = root.to_path_buf();
+        }
+    }
+
+    /// Birinchi argumentdagi yo'lni ildiz ichidagi haqiqiy yo'lga aylantiradi.
+    fn sandboxed_path(&self, args: &NativeArgs) -> Result<PathBuf, RuntimeError> {
+        let path = args.string(0)?;
+        self.sandbox
+            .resolve(path)
+            .map_err(|message| file_error(message, args.span(0)))
+    }
+}
+
+fn io_error(args: &NativeArgs) -> impl Fn(std::io::Error) -> RuntimeError {
+    let path = args.get(0).to_string();
+    let span = args.span;
+    move |e| file_error(format!("'{}': {}", path, e), span)
+}
+
+/// `fayl_oqi`, `fayl_yoz`, `fayl_qosh`, `fayl_bormi` va `papka_royxati`.
+pub(crate) fn register(interpreter: &mut Interpreter) {
+    interpreter.register_native(
+        "fayl_oqi",
+        1,
+        "fayl_oqi(yo'l): fayl matnini qaytaradi",
+        |interpreter, args| {
+            let path = interpreter.sandboxed_path(args)?;
+            let text = fs::read_to_string(path).map_err(io_error(args))?;
+            Ok(Value::String(Rc::from(text)))
+        },
+    );
+    interpreter.register_native(
+        "fayl_yoz",
+        2,
+        "fayl_yoz(yo'l, matn): faylga yozadi, eski mazmuni o'chiriladi",
+        |interpreter, args| {
+            let path = interpreter.sandboxed_path(args)?;
+            fs::write(path, args.get(1).to_string()).map_err(io_error(args))?;
+            Ok(Value::Number(0))
+        },
+    );
+    interpreter.register_native(
+        "fayl_qosh",
+        2,
+        "fayl_qosh(yo'l, matn): fayl oxiriga qo'shadi, fayl bo'lmasa yaratadi",
+        |interpreter, args| {
+            let path = interpreter.sandboxed_path(args)?;
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(args.get(1).to_string().as_bytes()))
+                .map_err(io_error(args))?;
+            Ok(Value::Number(0))
+        },
+    );
+    interpreter.register_native(
+        "fayl_bormi",
+        1,
+        "fayl_bormi(yo'l): fayl yoki papka mavjudligi",
+        |interpreter, args| {
+            let path = interpreter.sandboxed_path(args)?;
+            Ok(Value::Bool(path.exists()))
+        },
+    );
+    interpreter.register_native(
+        "papka_royxati",
+        1,
+        "papka_royxati(yo'l): papkadagi fayl va papkalar nomlari, alifbo tartibida",
+        |interpreter, args| {
+            let path = interpreter.sandboxed_path(args)?;
+            let mut names = Vec::new();
+            for entry in fs::read_dir(path).map_err(io_error(args))? {
+                let entry = entry.map_err(io_error(args))?;
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+            names.sort();
+            Ok(Value::Array(Rc::new(
+                names
+                    .into_iter()
+                    .map(|name| Value::String(Rc::from(name)))
+                    .collect(),
+            )))
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_support::{run_configured, temp_project};
+
+    /// Har bir dvigatel uchun alohida toza ildiz papkada bajaradi.
+    fn run(code: &str) -> Result<Interpreter, RuntimeError> {
+        let mut dirs = Vec::new();
+        let result = run_configured(code, |interpreter| {
+            let dir = temp_project(&[]);
+            interpreter.set_file_root(&dir);
+            dirs.push(dir);
+        });
+        for dir in dirs {
+            fs::remove_dir_all(dir).unwrap();
+        }
+        result
+    }
+
+    #[test]
+    fn test_write_append_read() {
+        let code = "\
+fayl_yoz(\"eslatma.txt\", \"salom\")
+fayl_qosh(\"eslatma.txt\", \", dunyo\")
+fayl_qosh(\"yangi.txt\", 42)
+matn1 = fayl_oqi(\"eslatma.txt\")
+matn2 = fayl_oqi(\"./yangi.txt\")
+bor = fayl_bormi(\"eslatma.txt\")
+yoq = fayl_bormi(\"boshqa.txt\")
+ruyxat = papka_royxati(\".\")";
+        let interpreter = run(code).unwrap();
+        let get = |name| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("matn1"), "salom, dunyo");
+        assert_eq!(get("matn2"), "42");
+        assert_eq!(get("bor"), "rost");
+        assert_eq!(get("yoq"), "yolg'on");
+        assert_eq!(get("ruyxat"), "[eslatma.txt; yangi.txt]");
+    }
+
+    #[test]
+    fn test_errors_are_runtime_errors() {
+        let err = run("x = fayl_oqi(\"yoq.txt\")").err().unwrap();
+        assert_eq!(err.kind.code(), "R009");
+        assert_eq!(err.span, Span::new(1, 5, 19));
+        let err = run("x = fayl_oqi(1)").err().unwrap();
+        assert_eq!(err.kind.code(), "R003");
+        let err = run("fayl_yoz(\"a.txt\")").err().unwrap();
+        assert_eq!(err.kind.code(), "R006");
+    }
+
+    #[test]
+    fn test_paths_cannot_escape_root() {
+        let dir = temp_project(&[("sir.txt", "maxfiy")]);
+        let root = dir.join("ildiz");
+        fs::create_dir_all(root.join("ichki")).unwrap();
+        let sandbox = Sandbox::new(&root);
+
+        assert!(sandbox.resolve("ichki/../a.txt").is_ok());
+        assert!(sandbox.resolve("ichki/yangi/fayl.txt").is_ok());
+        assert!(sandbox.resolve("../sir.txt").is_err());
+        assert!(sandbox.resolve("ichki/../../sir.txt").is_err());
+        assert!(
+            sandbox
+                .resolve(dir.join("sir.txt").to_str().unwrap())
+                .is_err()
+        );
+        assert!(sandbox.resolve("/etc/passwd").is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("sir.txt"), root.join("havola")).unwrap();
+            std::os::unix::fs::symlink(dir.join("yoq.txt"), root.join("osilgan")).unwrap();
+            assert!(sandbox.resolve("havola").is_err());
+            assert!(sandbox.resolve("osilgan").is_err());
+        }
+
+        let err = run_configured("x = fayl_oqi(\"../sir.txt\")", |interpreter| {
+            interpreter.set_file_root(&root)
+        })
+        .err()
+        .unwrap();
+        assert_eq!(err.kind.code(), "R009");
+        assert_eq!(err.span, Span::new(1, 14, 12));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
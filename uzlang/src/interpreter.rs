@@ -1,169 +1,668 @@
-use crate::parser::{Expr, Stmt};
-use std::collections::HashMap;
-use std::io::Read;
-use std::net::ToSocketAddrs;
+use crate::builtins;
+use crate::compiler::{self, Proto};
+use crate::diagnostic::{Diagnostic, Span};
+use crate::files::Sandbox;
+use crate::limits::{Budget, Limits};
+use crate::module::{Module, Modules};
+use crate::native::NativeFn;
+use crate::net::{NetworkPolicy, Resolver, SystemResolver};
+use crate::parser::{BinOp, Expr, ExprKind, FunctionDecl, Stmt, StmtKind, UnOp};
+use crate::stdio::Stdio;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
-use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Number(i64),
+    Float(f64),
     String(Rc<str>),
     Bool(bool),
     Array(Rc<Vec<Value>>),
+    Dict(Rc<BTreeMap<DictKey, Value>>),
+    Function(Rc<Function>),
+    Native(Rc<NativeFn>),
+}
+
+/// Foydalanuvchi funksiyasi qiymati.
+///
+/// Global darajada e'lon qilingan funksiyalar faqat global o'zgaruvchilarni ko'radi.
+/// Boshqa funksiya yoki tsikl ichida yaratilganlari esa yaratilgan paytdagi mahalliy
+/// o'zgaruvchilarning nusxasini (`captured`) o'zi bilan olib yuradi.
+#[derive(Debug)]
+pub struct Function {
+    pub name: Option<Rc<str>>,
+    pub decl: Rc<FunctionDecl>,
+    pub captured: Option<HashMap<Rc<str>, Value>>,
+    /// Bytecode dvigateli yaratgan funksiyalarning kompilyatsiya qilingan tanasi
+    pub code: Option<Rc<Proto>>,
+    /// Funksiya e'lon qilingan modul: uning global o'zgaruvchilari ko'rinadi
+    pub module: Rc<Module>,
+}
+
+impl Function {
+    pub(crate) fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("nomsiz")
+    }
+}
+
+// Functions are compared by identity: two definitions with the same body are still different values.
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// Lug'at kaliti: faqat matn yoki butun son bo'lishi mumkin.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DictKey {
+    Number(i64),
+    String(Rc<str>),
+}
+
+impl DictKey {
+    pub(crate) fn from_value(value: &Value, span: Span) -> Result<DictKey, RuntimeError> {
+        match value {
+            Value::Number(n) => Ok(DictKey::Number(*n)),
+            Value::String(s) => Ok(DictKey::String(s.clone())),
+            _ => Err(RuntimeError::type_mismatch(
+                format!(
+                    "Lug'at kaliti matn yoki butun son bo'lishi kerak, {} berildi",
+                    value.type_name()
+                ),
+                span,
+            )),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            DictKey::Number(n) => Value::Number(*n),
+            DictKey::String(s) => Value::String(s.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for DictKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DictKey::Number(n) => write!(f, "{}", n),
+            DictKey::String(s) => write!(f, "{}", s),
+        }
+    }
 }
 
 impl Value {
     pub fn empty_string() -> Self {
         Value::String(Rc::from(""))
     }
+
+    /// Qiymat turining o'zbekcha nomi (`turi` funksiyasi ham shuni qaytaradi).
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "son",
+            Value::Float(_) => "haqiqiy",
+            Value::String(_) => "matn",
+            Value::Bool(_) => "mantiq",
+            Value::Array(_) => "massiv",
+            Value::Dict(_) => "lug'at",
+            Value::Function(_) | Value::Native(_) => "funksiya",
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Float(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(Rc::from(s))
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(Rc::from(s))
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(elements: Vec<Value>) -> Self {
+        Value::Array(Rc::new(elements))
+    }
 }
 
+/// Haqiqiy sonni o'zbekcha yozadi: kasr qismi vergul bilan ajratiladi (3,5),
+/// butun qiymatlar ham haqiqiy ekanligi ko'rinishi uchun `2,0` shaklida chiqadi.
+pub(crate) fn format_float(n: f64) -> String {
+    if n.is_nan() {
+        return "son emas".to_string();
+    }
+    if n.is_infinite() {
+        return if n > 0.0 { "cheksiz" } else { "-cheksiz" }.to_string();
+    }
+    let text = if n.fract() == 0.0 {
+        format!("{:.1}", n)
+    } else {
+        n.to_string()
+    };
+    text.replace('.', ",")
+}
+
+/// `haqiqiy` va `son` uchun: nuqta ham, vergul ham kasr ajratuvchi bo'la oladi.
+pub(crate) fn parse_float(s: &str) -> Option<f64> {
+    s.trim().replace(',', ".").parse().ok()
+}
+
+// Elements are separated by ';' because ',' is already the decimal separator
+const ELEMENT_SEPARATOR: &str = "; ";
+
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Value::Number(n) => write!(f, "{}", n),
+            Value::Float(n) => write!(f, "{}", format_float(*n)),
             Value::String(s) => write!(f, "{}", s),
-            Value::Bool(b) => write!(f, "{}", b),
+            Value::Bool(b) => write!(f, "{}", if *b { "rost" } else { "yolg'on" }),
             Value::Array(arr) => {
                 write!(f, "[")?;
                 for (i, v) in arr.iter().enumerate() {
                     if i > 0 {
-                        write!(f, ", ")?;
+                        f.write_str(ELEMENT_SEPARATOR)?;
                     }
                     write!(f, "{}", v)?;
                 }
                 write!(f, "]")
             }
+            Value::Dict(dict) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in dict.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(ELEMENT_SEPARATOR)?;
+                    }
+                    write!(f, "{}: {}", k, v)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Function(func) => write!(f, "<funksiya {}>", func.display_name()),
+            Value::Native(native) => write!(f, "<funksiya {}>", native.name),
         }
     }
 }
 
-// Use Rc<str> for function parameters to avoid string cloning on every function call.
-type FunctionDef = (Rc<Vec<Rc<str>>>, Rc<Vec<Stmt>>);
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeErrorKind {
+    UndefinedVariable(String),
+    UnknownFunction(String),
+    TypeMismatch(String),
+    IndexOutOfBounds {
+        index: i64,
+        len: usize,
+    },
+    KeyNotFound(String),
+    Module(String),
+    File(String),
+    Limit(String),
+    /// `xato_chiqar` bilan dasturning o'zi chiqargan xato
+    Raised(String),
+    /// Nolga bo'lish, butun son chegarasidan chiqish kabi hisob xatolari
+    Arithmetic(String),
+    ArgumentCount {
+        name: String,
+        expected: usize,
+        given: usize,
+    },
+}
 
-pub struct Interpreter {
-    env_stack: Vec<HashMap<Rc<str>, Value>>,
-    functions: HashMap<String, FunctionDef>,
+impl RuntimeErrorKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuntimeErrorKind::UndefinedVariable(_) => "R001",
+            RuntimeErrorKind::UnknownFunction(_) => "R002",
+            RuntimeErrorKind::TypeMismatch(_) => "R003",
+            RuntimeErrorKind::IndexOutOfBounds { .. } => "R004",
+            RuntimeErrorKind::ArgumentCount { .. } => "R006",
+            RuntimeErrorKind::KeyNotFound(_) => "R007",
+            RuntimeErrorKind::Module(_) => "R008",
+            RuntimeErrorKind::File(_) => "R009",
+            RuntimeErrorKind::Limit(_) => "R010",
+            RuntimeErrorKind::Raised(_) => "R011",
+            RuntimeErrorKind::Arithmetic(_) => "R012",
+        }
+    }
 }
 
-fn is_safe_ip(ip: std::net::IpAddr) -> bool {
-    match ip {
-        std::net::IpAddr::V4(ipv4) => {
-            let octets = ipv4.octets();
-            // Loopback 127.0.0.0/8
-            if octets[0] == 127 {
-                return false;
-            }
-            // Private 10.0.0.0/8
-            if octets[0] == 10 {
-                return false;
-            }
-            // Private 172.16.0.0/12
-            if octets[0] == 172 && (16..=31).contains(&octets[1]) {
-                return false;
-            }
-            // Private 192.168.0.0/16
-            if octets[0] == 192 && octets[1] == 168 {
-                return false;
-            }
-            // Link-local 169.254.0.0/16
-            if octets[0] == 169 && octets[1] == 254 {
-                return false;
-            }
-            // Current network 0.0.0.0/8
-            if octets[0] == 0 {
-                return false;
-            }
-            // CGNAT 100.64.0.0/10
-            if octets[0] == 100 && (64..=127).contains(&octets[1]) {
-                return false;
-            }
-            // Broadcast 255.255.255.255
-            if octets == [255, 255, 255, 255] {
-                return false;
+impl std::fmt::Display for RuntimeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RuntimeErrorKind::UndefinedVariable(name) => {
+                write!(f, "O'zgaruvchi topilmadi: {}", name)
             }
-            true
+            RuntimeErrorKind::UnknownFunction(name) => write!(f, "Funksiya topilmadi: {}", name),
+            RuntimeErrorKind::TypeMismatch(message) => write!(f, "{}", message),
+            RuntimeErrorKind::IndexOutOfBounds { index, len } => write!(
+                f,
+                "Indeks chegaradan tashqarida: {} (uzunlik {})",
+                index, len
+            ),
+            RuntimeErrorKind::KeyNotFound(key) => write!(f, "Lug'atda kalit topilmadi: {}", key),
+            RuntimeErrorKind::Module(message) => write!(f, "{}", message),
+            RuntimeErrorKind::File(message) => write!(f, "Fayl xatosi: {}", message),
+            RuntimeErrorKind::Limit(message) => write!(f, "Cheklovdan oshildi: {}", message),
+            RuntimeErrorKind::Raised(message) => write!(f, "{}", message),
+            RuntimeErrorKind::Arithmetic(message) => write!(f, "Hisoblash xatosi: {}", message),
+            RuntimeErrorKind::ArgumentCount {
+                name,
+                expected,
+                given,
+            } => write!(
+                f,
+                "'{}' funksiyasi {} ta argument kutadi, lekin {} ta berildi",
+                name, expected, given
+            ),
         }
-        std::net::IpAddr::V6(ipv6) => {
-            if ipv6.is_loopback() {
-                return false;
-            }
-            if ipv6.is_unspecified() {
-                return false;
-            }
-            let segments = ipv6.segments();
-            // Unique local fc00::/7
-            if (segments[0] & 0xfe00) == 0xfc00 {
-                return false;
-            }
-            // Link-local fe80::/10
-            if (segments[0] & 0xffc0) == 0xfe80 {
-                return false;
+    }
+}
+
+// Identical consecutive trace lines printed before they are summarized
+const MAX_REPEATED_FRAMES: usize = 3;
+
+/// Xato yuz berganda faol bo'lgan foydalanuvchi funksiyasi chaqiruvi.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceFrame {
+    pub function: String,
+    pub span: Span,
+}
+
+/// Dasturni to'xtatuvchi ijro xatosi. `trace` eng ichki chaqiruvdan boshlanadi.
+///
+/// Xato boshqa modulda yuz bergan bo'lsa, `span` shu modulga kirilgan joyni ko'rsatadi,
+/// asl xato esa `inner` da saqlanadi.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub span: Span,
+    pub trace: Vec<TraceFrame>,
+    pub inner: Option<Box<(Rc<Module>, RuntimeError)>>,
+}
+
+impl RuntimeError {
+    pub fn new(kind: RuntimeErrorKind, span: Span) -> Self {
+        RuntimeError {
+            kind,
+            span,
+            trace: Vec::new(),
+            inner: None,
+        }
+    }
+
+    pub(crate) fn type_mismatch(message: impl Into<String>, span: Span) -> Self {
+        RuntimeError::new(RuntimeErrorKind::TypeMismatch(message.into()), span)
+    }
+
+    pub(crate) fn arithmetic(message: impl Into<String>, span: Span) -> Self {
+        RuntimeError::new(RuntimeErrorKind::Arithmetic(message.into()), span)
+    }
+
+    /// `urinib ko'r` ushlay oladigan xatomi. Cheklovlar dasturni har doim to'xtatadi,
+    /// aks holda cheksiz tsikl ularni ushlab, ishlashda davom etishi mumkin edi.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(self.kind, RuntimeErrorKind::Limit(_))
+    }
+
+    /// `xato bo'lsa` o'zgaruvchisiga beriladigan qiymat: `xabar`, `turi` (xato kodi)
+    /// va `qator` kalitli lug'at.
+    pub fn to_value(&self) -> Value {
+        let fields = [
+            ("xabar", Value::from(self.kind.to_string())),
+            ("turi", Value::from(self.kind.code())),
+            ("qator", Value::Number(self.span.line as i64)),
+        ];
+        Value::Dict(Rc::new(
+            fields
+                .into_iter()
+                .map(|(key, value)| (DictKey::String(Rc::from(key)), value))
+                .collect(),
+        ))
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.kind.code(), self.kind.to_string(), self.span)
+    }
+
+    /// Xatoni manba qatori va chaqiruvlar izi bilan chiqaradi.
+    pub fn render(&self, source: &str, filename: &str) -> String {
+        let mut out = String::new();
+        let mut trace = Vec::new();
+        self.render_into(source, filename, (source, filename), &mut out, &mut trace);
+        if !trace.is_empty() {
+            out.push_str("Chaqiruvlar izi (eng ichkisi birinchi):\n");
+            // Deep recursion repeats the same frame; only the first few copies are shown
+            let mut i = 0;
+            while i < trace.len() {
+                let repeats = trace[i..].iter().take_while(|l| **l == trace[i]).count();
+                for line in &trace[i..i + repeats.min(MAX_REPEATED_FRAMES)] {
+                    out.push_str(line);
+                }
+                if repeats > MAX_REPEATED_FRAMES {
+                    out.push_str(&format!(
+                        "  ... yuqoridagi qator yana {} marta takrorlanadi\n",
+                        repeats - MAX_REPEATED_FRAMES
+                    ));
+                }
+                i += repeats;
             }
-            // IPv4-mapped ::ffff:0:0/96
-            if let Some(ipv4) = ipv6.to_ipv4() {
-                return is_safe_ip(std::net::IpAddr::V4(ipv4));
+        }
+        out
+    }
+
+    // `main` is the host program's source and name, used when a module calls back into it
+    fn render_into(
+        &self,
+        source: &str,
+        filename: &str,
+        main: (&str, &str),
+        out: &mut String,
+        trace: &mut Vec<String>,
+    ) {
+        match &self.inner {
+            Some(inner) => {
+                let (module, err) = &**inner;
+                let (module_source, module_name) = module.source_or(main.0, main.1);
+                err.render_into(module_source, module_name, main, out, trace);
+                // A call into a module function is already listed by its own trace frame
+                if self
+                    .trace
+                    .first()
+                    .is_none_or(|frame| frame.span != self.span)
+                {
+                    trace.push(format!(
+                        "  '{}' modulida, {}:{}:{} da ulangan\n",
+                        module_name, filename, self.span.line, self.span.column
+                    ));
+                }
             }
-            true
+            None => out.push_str(&self.to_diagnostic().render(source, filename)),
+        }
+        for frame in &self.trace {
+            trace.push(format!(
+                "  '{}' funksiyasida, {}:{}:{} da chaqirilgan\n",
+                frame.function, filename, frame.span.line, frame.span.column
+            ));
         }
     }
 }
 
-fn create_safe_client(url_str: &str) -> Result<(reqwest::blocking::Client, String), &'static str> {
-    if let Ok(url) = reqwest::Url::parse(url_str) {
-        if url.scheme() != "http" && url.scheme() != "https" {
-            return Err("Faqat HTTP/HTTPS ruxsat etilgan");
+type EvalResult = Result<Value, RuntimeError>;
+
+pub(crate) const DIVISION_BY_ZERO: &str = "nolga bo'lish mumkin emas";
+pub(crate) const INTEGER_OVERFLOW: &str = "natija butun son chegarasidan chiqib ketdi";
+
+/// Blok bajarilgandan keyin boshqaruv qayerga o'tishi kerakligi.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlFlow {
+    Normal,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+type ExecResult = Result<ControlFlow, RuntimeError>;
+
+/// Dasturni bajarish usuli.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Engine {
+    /// AST daraxti bo'ylab to'g'ridan-to'g'ri yuradi (eski dvigatel).
+    Tree,
+    /// Avval bytecode'ga kompilyatsiya qiladi, so'ng stek mashinasida bajaradi.
+    #[default]
+    Bytecode,
+}
+
+pub struct Interpreter {
+    // env_stack[0] is the global scope; the current function's scopes start at frame_base
+    env_stack: Vec<HashMap<Rc<str>, Value>>,
+    frame_base: usize,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    engine: Engine,
+    pub(crate) modules: Modules,
+    pub(crate) sandbox: Sandbox,
+    pub(crate) natives: HashMap<Rc<str>, Rc<NativeFn>>,
+    pub(crate) stdio: Stdio,
+    pub(crate) limits: Limits,
+    pub(crate) budget: Budget,
+    pub(crate) network: NetworkPolicy,
+    pub(crate) resolver: Rc<dyn Resolver>,
+}
+
+/// `==` amali. Har qanday ikki qiymat uchun aniqlangan, turi har xil qiymatlar teng emas.
+/// Butun va haqiqiy sonlar, massiv va lug'atlar ichida ham, haqiqiy songa aylantirib
+/// solishtiriladi: `[1] == [1.0]`.
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Float(l), Value::Number(r)) => *l == *r as f64,
+        (Value::Number(l), Value::Float(r)) => *l as f64 == *r,
+        (Value::Array(l), Value::Array(r)) => {
+            l.len() == r.len() && l.iter().zip(r.iter()).all(|(l, r)| values_equal(l, r))
         }
-        if let Some(host) = url.host_str() {
-            // Defense in depth: Check known bad hosts (string based)
-            if host == "localhost" || host == "::1" || host == "[::1]" || host.starts_with("127.") {
-                return Err("Mahalliy yoki xususiy tarmoqqa ulanish taqiqlangan");
-            }
+        (Value::Dict(l), Value::Dict(r)) => {
+            l.len() == r.len()
+                && l.iter()
+                    .zip(r.iter())
+                    .all(|((lk, lv), (rk, rv))| lk == rk && values_equal(lv, rv))
+        }
+        _ => left == right,
+    }
+}
 
-            // Resolve DNS and pick the first valid address to pin
-            let port = url.port_or_known_default().unwrap_or(80);
-            let addr_str = format!("{}:{}", host, port);
+/// Butun son amalining natijasi; `None` chegaradan chiqishni bildiradi.
+fn checked(result: Option<i64>, span: Span) -> EvalResult {
+    result
+        .map(Value::Number)
+        .ok_or_else(|| RuntimeError::arithmetic(INTEGER_OVERFLOW, span))
+}
 
-            if let Ok(mut addrs) = addr_str.to_socket_addrs() {
-                if let Some(addr) = addrs.next() {
-                    // Check if the resolved IP is safe
-                    if !is_safe_ip(addr.ip()) {
-                        return Err("Mahalliy yoki xususiy tarmoqqa ulanish taqiqlangan");
-                    }
+/// Butun sonni butun darajaga ko'taradi. Manfiy daraja haqiqiy son beradi: `2 ** (0 - 1)` = 0,5.
+fn integer_power(base: i64, exponent: i64, span: Span) -> EvalResult {
+    if exponent < 0 {
+        // 0 ** -n is 1 / 0 ** n
+        if base == 0 {
+            return Err(RuntimeError::arithmetic(DIVISION_BY_ZERO, span));
+        }
+        return Ok(Value::Float((base as f64).powf(exponent as f64)));
+    }
+    // These bases never overflow, however large the exponent
+    match base {
+        0 | 1 => return Ok(Value::Number(if exponent == 0 { 1 } else { base })),
+        -1 => return Ok(Value::Number(if exponent % 2 == 0 { 1 } else { -1 })),
+        _ => {}
+    }
+    let result = u32::try_from(exponent)
+        .ok()
+        .and_then(|exponent| base.checked_pow(exponent));
+    checked(result, span)
+}
+
+/// `qiymat[indeks]` ifodasini hisoblaydi.
+pub(crate) fn index_value(
+    target_val: Value,
+    index_val: Value,
+    target_span: Span,
+    index_span: Span,
+) -> EvalResult {
+    let elements = match target_val {
+        Value::Array(elements) => elements,
+        Value::Dict(dict) => {
+            let key = DictKey::from_value(&index_val, index_span)?;
+            return dict.get(&key).cloned().ok_or_else(|| {
+                RuntimeError::new(RuntimeErrorKind::KeyNotFound(key.to_string()), index_span)
+            });
+        }
+        // Matn harflar bo'yicha indekslanadi va bitta harfli matn qaytaradi
+        Value::String(s) => {
+            let idx = index_number(index_val, index_span)?;
+            let found = usize::try_from(idx).ok().and_then(|i| s.chars().nth(i));
+            return found.map(|c| Value::from(c.to_string())).ok_or_else(|| {
+                RuntimeError::new(
+                    RuntimeErrorKind::IndexOutOfBounds {
+                        index: idx,
+                        len: s.chars().count(),
+                    },
+                    index_span,
+                )
+            });
+        }
+        _ => {
+            return Err(RuntimeError::type_mismatch(
+                format!(
+                    "Faqat massiv, lug'at yoki matn indekslanadi, {} berildi",
+                    target_val.type_name()
+                ),
+                target_span,
+            ));
+        }
+    };
+    let idx = index_number(index_val, index_span)?;
+    if idx >= 0 && (idx as usize) < elements.len() {
+        Ok(elements[idx as usize].clone())
+    } else {
+        Err(RuntimeError::new(
+            RuntimeErrorKind::IndexOutOfBounds {
+                index: idx,
+                len: elements.len(),
+            },
+            index_span,
+        ))
+    }
+}
 
-                    // Pin the resolved IP address to prevent DNS rebinding/TOCTOU
-                    let client = reqwest::blocking::Client::builder()
-                        .resolve(host, addr)
-                        .redirect(reqwest::redirect::Policy::none())
-                        .timeout(Duration::from_secs(10))
-                        .build()
-                        .map_err(|_| "Mijoz yaratishda xatolik")?;
+fn index_number(index_val: Value, index_span: Span) -> Result<i64, RuntimeError> {
+    match index_val {
+        Value::Number(idx) => Ok(idx),
+        _ => Err(RuntimeError::type_mismatch(
+            "Indeks raqam bo'lishi kerak",
+            index_span,
+        )),
+    }
+}
 
-                    return Ok((client, url_str.to_string()));
-                }
-            }
+/// `nom[indeks] = qiymat`: massiv yoki lug'atni joyida o'zgartiradi (copy-on-write).
+pub(crate) fn assign_index(
+    val: &mut Value,
+    name: &str,
+    index_val: Value,
+    value_val: Value,
+    span: Span,
+    index_span: Span,
+) -> Result<(), RuntimeError> {
+    let rc_arr = match val {
+        Value::Array(rc_arr) => rc_arr,
+        Value::Dict(rc_dict) => {
+            let key = DictKey::from_value(&index_val, index_span)?;
+            Rc::make_mut(rc_dict).insert(key, value_val);
+            return Ok(());
+        }
+        _ => {
+            return Err(RuntimeError::type_mismatch(
+                format!("O'zgaruvchi massiv yoki lug'at emas: {}", name),
+                span,
+            ));
         }
+    };
+    let Value::Number(idx) = index_val else {
+        return Err(RuntimeError::type_mismatch(
+            "Indeks raqam bo'lishi kerak",
+            index_span,
+        ));
+    };
+    let elements = Rc::make_mut(rc_arr);
+    if idx >= 0 && (idx as usize) < elements.len() {
+        elements[idx as usize] = value_val;
+        return Ok(());
     }
-    Err("Noto'g'ri manzil format")
+    Err(RuntimeError::new(
+        RuntimeErrorKind::IndexOutOfBounds {
+            index: idx,
+            len: elements.len(),
+        },
+        index_span,
+    ))
 }
 
-const MAX_RESPONSE_SIZE: u64 = 5 * 1024 * 1024;
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
 
 impl Interpreter {
     pub fn new() -> Self {
-        Interpreter {
+        let mut interpreter = Interpreter {
             env_stack: vec![HashMap::new()],
-            functions: HashMap::new(),
+            frame_base: 1,
+            diagnostics: Vec::new(),
+            engine: Engine::default(),
+            modules: Modules::new(),
+            sandbox: Sandbox::default(),
+            natives: HashMap::new(),
+            stdio: Stdio::default(),
+            limits: Limits::default(),
+            budget: Budget::default(),
+            network: NetworkPolicy::default(),
+            resolver: Rc::new(SystemResolver),
+        };
+        builtins::register(&mut interpreter);
+        interpreter
+    }
+
+    pub fn set_engine(&mut self, engine: Engine) {
+        self.engine = engine;
+    }
+
+    pub(crate) fn globals_mut(&mut self) -> &mut HashMap<Rc<str>, Value> {
+        &mut self.env_stack[0]
+    }
+
+    /// Ijro vaqtida yig'ilgan ogohlantirishlarni qaytaradi (masalan, tarmoq xatolari).
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    pub(crate) fn warn(&mut self, code: &'static str, message: impl Into<String>, span: Span) {
+        self.diagnostics
+            .push(Diagnostic::warning(code, message, span));
+    }
+
+    /// Joriy funksiya doiralarida, so'ng global doirada o'zgaruvchini qidiradi.
+    /// Chaqiruvchi funksiyaning mahalliy o'zgaruvchilari ko'rinmaydi.
+    fn lookup_mut(&mut self, name: &str) -> Option<&mut Value> {
+        let (globals, locals) = self.env_stack.split_at_mut(1);
+        for scope in locals[self.frame_base - 1..].iter_mut().rev() {
+            if let Some(val) = scope.get_mut(name) {
+                return Some(val);
+            }
         }
+        globals[0].get_mut(name)
     }
 
     pub fn set_variable(&mut self, name: &str, val: Value) {
-        for scope in self.env_stack.iter_mut().rev() {
-            if let Some(existing_val) = scope.get_mut(name) {
-                *existing_val = val;
-                return;
-            }
+        if let Some(existing_val) = self.lookup_mut(name) {
+            *existing_val = val;
+            return;
         }
 
         if let Some(scope) = self.env_stack.last_mut() {
@@ -171,451 +670,1130 @@ impl Interpreter {
         }
     }
 
-    pub fn get_variable(&self, name: &str) -> Value {
-        for scope in self.env_stack.iter().rev() {
+    pub fn get_variable(&self, name: &str) -> Option<Value> {
+        for scope in self.env_stack[self.frame_base..].iter().rev() {
             if let Some(val) = scope.get(name) {
-                return val.clone();
+                return Some(val.clone());
+            }
+        }
+        self.env_stack[0].get(name).cloned()
+    }
+
+    /// Dastur ichidagi nom: avval o'zgaruvchilar, so'ng native funksiyalar.
+    fn lookup(&self, name: &str) -> Option<Value> {
+        self.get_variable(name)
+            .or_else(|| self.natives.get(name).cloned().map(Value::Native))
+    }
+
+    /// Global o'zgaruvchilarni nomi bo'yicha tartiblab qaytaradi.
+    pub fn globals(&self) -> Vec<(&str, &Value)> {
+        let mut globals: Vec<(&str, &Value)> = self.env_stack[0]
+            .iter()
+            .map(|(name, val)| (name.as_ref(), val))
+            .collect();
+        globals.sort_by(|a, b| a.0.cmp(b.0));
+        globals
+    }
+
+    /// Yangi funksiya qiymatini yaratadi va joriy mahalliy o'zgaruvchilarni unga biriktiradi.
+    fn make_function(&self, name: Option<&str>, decl: &Rc<FunctionDecl>) -> Value {
+        let captured = if self.env_stack.len() > self.frame_base {
+            let mut captured = HashMap::new();
+            // Ichki doiralar tashqi doiralardagi bir xil nomlarni yopadi
+            for scope in &self.env_stack[self.frame_base..] {
+                for (k, v) in scope {
+                    captured.insert(k.clone(), v.clone());
+                }
+            }
+            Some(captured)
+        } else {
+            None
+        };
+        Value::Function(Rc::new(Function {
+            name: name.map(Rc::from),
+            decl: Rc::clone(decl),
+            captured,
+            code: None,
+            module: Rc::clone(&self.modules.current),
+        }))
+    }
+
+    fn call_function(&mut self, func: &Rc<Function>, args: Vec<Value>, span: Span) -> EvalResult {
+        let params = &func.decl.params;
+        if params.len() != args.len() {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::ArgumentCount {
+                    name: func.display_name().to_string(),
+                    expected: params.len(),
+                    given: args.len(),
+                },
+                span,
+            ));
+        }
+
+        self.enter_call(span)?;
+        let saved_base = self.frame_base;
+        self.frame_base = self.env_stack.len();
+        if let Some(captured) = &func.captured {
+            let mut scope = captured.clone();
+            // Mahalliy nomli funksiya o'zini chaqira olishi uchun
+            if let Some(name) = &func.name {
+                scope.insert(name.clone(), Value::Function(Rc::clone(func)));
+            }
+            self.env_stack.push(scope);
+        }
+
+        // Create new scope
+        // Bolt: Pre-allocate HashMap capacity to avoid reallocation for function scopes
+        let mut scope = HashMap::with_capacity(params.len());
+        for (param, val) in params.iter().zip(args) {
+            scope.insert(param.clone(), val);
+        }
+
+        self.env_stack.push(scope);
+        let result = self.with_stack(|this| {
+            this.run_in_module(&func.module, span, |this| {
+                this.execute_block(&func.decl.body)
+            })
+        });
+        self.leave_call();
+        self.env_stack.truncate(self.frame_base);
+        self.frame_base = saved_base;
+
+        match result {
+            Ok(ControlFlow::Return(val)) => Ok(val),
+            Ok(_) => Ok(Value::Number(0)), // Default return 0
+            Err(mut err) => {
+                err.trace.push(TraceFrame {
+                    function: func.display_name().to_string(),
+                    span,
+                });
+                Err(err)
+            }
+        }
+    }
+
+    /// Dasturni tanlangan dvigatelda bajaradi.
+    pub fn execute(&mut self, stmts: &[Stmt]) -> ExecResult {
+        let outermost = self.begin_run();
+        let result = match self.engine {
+            Engine::Tree => self.execute_block(stmts),
+            Engine::Bytecode => {
+                let chunk = compiler::compile_program(stmts);
+                self.run_program(&chunk).map(|result| match result {
+                    Some(val) => ControlFlow::Return(val),
+                    None => ControlFlow::Normal,
+                })
             }
+        };
+        if outermost {
+            self.end_run();
         }
-        Value::Number(0)
+        result
     }
 
-    pub fn execute(&mut self, stmts: &[Stmt]) -> Option<Value> {
+    fn execute_block(&mut self, stmts: &[Stmt]) -> ExecResult {
         for stmt in stmts {
-            if let Some(val) = self.execute_stmt(stmt) {
-                return Some(val);
+            let flow = self.execute_stmt(stmt)?;
+            if flow != ControlFlow::Normal {
+                return Ok(flow);
             }
         }
-        None
+        Ok(ControlFlow::Normal)
     }
 
-    fn execute_stmt(&mut self, stmt: &Stmt) -> Option<Value> {
-        match stmt {
-            Stmt::Print(expr) => {
-                let val = self.evaluate(expr);
-                println!("{}", val);
-                None
+    fn execute_stmt(&mut self, stmt: &Stmt) -> ExecResult {
+        self.step(stmt.span)?;
+        match &stmt.kind {
+            StmtKind::Print(expr) => {
+                let val = self.evaluate(expr)?;
+                self.print(&val);
+                Ok(ControlFlow::Normal)
             }
-            Stmt::If(cond, body) => {
-                let val = self.evaluate(cond);
-                if self.is_truthy(val) {
-                    return self.execute(body);
+            StmtKind::If(branches, else_body) => {
+                for (cond, body) in branches {
+                    let val = self.evaluate(cond)?;
+                    if self.is_truthy(val) {
+                        return self.execute_block(body);
+                    }
+                }
+                match else_body {
+                    Some(body) => self.execute_block(body),
+                    None => Ok(ControlFlow::Normal),
                 }
-                None
             }
-            Stmt::Loop(cond, body) => {
+            StmtKind::Loop(cond, body) => {
                 while {
-                    let val = self.evaluate(cond);
+                    let val = self.evaluate(cond)?;
                     self.is_truthy(val)
                 } {
-                    if let Some(ret) = self.execute(body) {
-                        return Some(ret);
+                    self.step(stmt.span)?;
+                    match self.execute_block(body)? {
+                        ControlFlow::Break => break,
+                        ControlFlow::Normal | ControlFlow::Continue => {}
+                        ret @ ControlFlow::Return(_) => return Ok(ret),
                     }
                 }
-                None
+                Ok(ControlFlow::Normal)
             }
-            Stmt::For(var_name, collection, body) => {
-                let collection_val = self.evaluate(collection);
-                if let Value::Array(elements) = collection_val {
+            StmtKind::For(var_name, collection, body) => {
+                let collection_val = self.evaluate(collection)?;
+                let items = match collection_val {
+                    Value::Array(ref elements) => Some(elements.clone()),
+                    // Lug'at bo'yicha tsikl kalitlarni beradi
+                    Value::Dict(ref dict) => {
+                        Some(Rc::new(dict.keys().map(DictKey::to_value).collect()))
+                    }
+                    _ => None,
+                };
+                if let Some(elements) = items {
                     let var_name_rc: Rc<str> = Rc::from(var_name.as_str());
                     // Optimization: Reuse the same HashMap for scope to avoid allocation in every iteration
                     let mut scope = HashMap::new();
                     for element in elements.iter() {
+                        self.step(stmt.span)?;
                         scope.insert(var_name_rc.clone(), element.clone());
                         self.env_stack.push(scope);
 
-                        let ret = self.execute(body);
+                        let ret = self.execute_block(body);
                         // Retrieve the scope to reuse it
                         scope = self.env_stack.pop().expect("Stack error in For loop");
                         // Clear variables declared in the loop body, but keep allocation
                         scope.clear();
 
-                        if let Some(val) = ret {
-                            return Some(val);
+                        match ret? {
+                            ControlFlow::Break => break,
+                            ControlFlow::Normal | ControlFlow::Continue => {}
+                            ret @ ControlFlow::Return(_) => return Ok(ret),
                         }
                     }
+                    Ok(ControlFlow::Normal)
                 } else {
-                    eprintln!("Xatolik: 'uchun' faqat massivlar bilan ishlaydi");
+                    Err(RuntimeError::type_mismatch(
+                        format!(
+                            "'uchun' faqat massiv va lug'atlar bilan ishlaydi, {} berildi",
+                            collection_val.type_name()
+                        ),
+                        collection.span,
+                    ))
                 }
-                None
             }
-            Stmt::Assign(name, expr) => {
-                let val = self.evaluate(expr);
+            StmtKind::Assign(name, expr) => {
+                let val = self.evaluate(expr)?;
                 self.set_variable(name, val);
-                None
-            }
-            Stmt::AssignIndex(name, index_expr, value_expr) => {
-                let index_val = self.evaluate(index_expr);
-                let value_val = self.evaluate(value_expr);
-
-                let mut found = false;
-                for scope in self.env_stack.iter_mut().rev() {
-                    if let Some(val) = scope.get_mut(name.as_str()) {
-                        found = true;
-                        if let Value::Array(rc_arr) = val {
-                            if let Value::Number(idx) = index_val {
-                                let elements = Rc::make_mut(rc_arr);
-                                if idx >= 0 && (idx as usize) < elements.len() {
-                                    elements[idx as usize] = value_val;
-                                } else {
-                                    eprintln!("Xatolik: Indeks chegaradan tashqarida: {}", idx);
-                                }
-                            } else {
-                                eprintln!("Xatolik: Indeks raqam bo'lishi kerak");
-                            }
-                        } else {
-                            eprintln!("Xatolik: O'zgaruvchi massiv emas: {}", name);
-                        }
-                        break;
-                    }
-                }
-                if !found {
-                    eprintln!("Xatolik: O'zgaruvchi topilmadi: {}", name);
-                }
+                Ok(ControlFlow::Normal)
+            }
+            StmtKind::AssignIndex(name, index_expr, value_expr) => {
+                let index_val = self.evaluate(index_expr)?;
+                let value_val = self.evaluate(value_expr)?;
 
-                if !found {
-                    eprintln!("Xatolik: O'zgaruvchi topilmadi: {}", name);
+                let limits = self.limits;
+                if let Some(val) = self.lookup_mut(name) {
+                    assign_index(val, name, index_val, value_val, stmt.span, index_expr.span)?;
+                    limits.check_size(val, stmt.span)?;
+                    return Ok(ControlFlow::Normal);
                 }
 
-                None
+                Err(RuntimeError::new(
+                    RuntimeErrorKind::UndefinedVariable(name.clone()),
+                    stmt.span,
+                ))
+            }
+            StmtKind::Function(name, decl) => {
+                // Funksiya har doim joriy (eng ichki) doirada e'lon qilinadi
+                let func = self.make_function(Some(name), decl);
+                if let Some(scope) = self.env_stack.last_mut() {
+                    scope.insert(Rc::from(name.as_str()), func);
+                }
+                Ok(ControlFlow::Normal)
             }
-            Stmt::Function(name, params, body) => {
-                let params_rc: Vec<Rc<str>> = params.iter().map(|p| Rc::from(p.as_str())).collect();
-                self.functions
-                    .insert(name.clone(), (Rc::new(params_rc), Rc::new(body.clone())));
-                None
+            StmtKind::Return(expr) => Ok(ControlFlow::Return(self.evaluate(expr)?)),
+            StmtKind::Import(path, alias) => {
+                self.import_module(path, alias.as_deref(), stmt.span)?;
+                Ok(ControlFlow::Normal)
             }
-            Stmt::Return(expr) => Some(self.evaluate(expr)),
-            Stmt::Expr(expr) => {
-                self.evaluate(expr);
-                None
+            StmtKind::Try(body, name, handler) => match self.execute_block(body) {
+                Err(err) if err.is_catchable() => {
+                    if let Some(name) = name {
+                        self.set_variable(name, err.to_value());
+                    }
+                    self.execute_block(handler)
+                }
+                result => result,
+            },
+            StmtKind::Break => Ok(ControlFlow::Break),
+            StmtKind::Continue => Ok(ControlFlow::Continue),
+            StmtKind::Expr(expr) => {
+                self.evaluate(expr)?;
+                Ok(ControlFlow::Normal)
             }
         }
     }
 
-    fn evaluate(&mut self, expr: &Expr) -> Value {
-        match expr {
-            Expr::Number(n) => Value::Number(*n),
-            Expr::StringLiteral(s) => Value::String(Rc::from(s.as_str())),
-            Expr::Identifier(name) => self.get_variable(name),
-            Expr::Input => {
-                let mut input = String::new();
-                if std::io::stdin().read_line(&mut input).is_ok() {
-                    Value::String(Rc::from(input.trim()))
-                } else {
-                    Value::empty_string()
-                }
-            }
-            Expr::Array(elements) => {
+    fn evaluate(&mut self, expr: &Expr) -> EvalResult {
+        match &expr.kind {
+            ExprKind::Number(n) => Ok(Value::Number(*n)),
+            ExprKind::Float(n) => Ok(Value::Float(*n)),
+            ExprKind::StringLiteral(s) => Ok(Value::String(Rc::from(s.as_str()))),
+            ExprKind::Bool(b) => Ok(Value::Bool(*b)),
+            ExprKind::Identifier(name) => self.lookup(name).ok_or_else(|| {
+                RuntimeError::new(RuntimeErrorKind::UndefinedVariable(name.clone()), expr.span)
+            }),
+            ExprKind::Input => Ok(self.read_input()),
+            ExprKind::Array(elements) => {
                 // Bolt: Pre-allocate vector capacity to avoid reallocation
                 let mut values = Vec::with_capacity(elements.len());
                 for e in elements {
-                    values.push(self.evaluate(e));
+                    values.push(self.evaluate(e)?);
                 }
-                Value::Array(Rc::new(values))
-            }
-            Expr::Index(target, index) => {
-                let target_val = self.evaluate(target);
-                let index_val = self.evaluate(index);
-
-                if let Value::Array(elements) = target_val {
-                    if let Value::Number(idx) = index_val {
-                        if idx >= 0 && (idx as usize) < elements.len() {
-                            elements[idx as usize].clone()
-                        } else {
-                            eprintln!("Xatolik: Indeks chegaradan tashqarida: {}", idx);
-                            Value::Number(0)
-                        }
-                    } else {
-                        eprintln!("Xatolik: Indeks raqam bo'lishi kerak");
-                        Value::Number(0)
-                    }
-                } else {
-                    eprintln!("Xatolik: Massiv indekslanishi kerak");
-                    Value::Number(0)
+                Ok(Value::Array(Rc::new(values)))
+            }
+            ExprKind::Dict(entries) => {
+                let mut dict = BTreeMap::new();
+                for (key_expr, value_expr) in entries {
+                    let key = self.evaluate(key_expr)?;
+                    let key = DictKey::from_value(&key, key_expr.span)?;
+                    dict.insert(key, self.evaluate(value_expr)?);
                 }
+                Ok(Value::Dict(Rc::new(dict)))
             }
-            Expr::Call(name, args) => {
+            ExprKind::Index(target, index) => {
+                let target_val = self.evaluate(target)?;
+                let index_val = self.evaluate(index)?;
+                index_value(target_val, index_val, target.span, index.span)
+            }
+            ExprKind::Function(decl) => Ok(self.make_function(None, decl)),
+            ExprKind::Call(callee, args) => {
+                // Callee expressions other than plain names are evaluated before the arguments
+                let callee_val = match &callee.kind {
+                    ExprKind::Identifier(_) => None,
+                    _ => Some(self.evaluate(callee)?),
+                };
+
                 // Bolt: Pre-allocate vector capacity to avoid reallocation
                 let mut arg_values = Vec::with_capacity(args.len());
                 for arg in args {
-                    arg_values.push(self.evaluate(arg));
+                    arg_values.push(self.evaluate(arg)?);
                 }
 
-                // Native functions
-                match name.as_str() {
-                    "son" => {
-                        if let Some(val) = arg_values.first() {
-                            match val {
-                                Value::String(s) => {
-                                    return Value::Number(s.trim().parse().unwrap_or(0));
-                                }
-                                Value::Number(n) => return Value::Number(*n),
-                                _ => return Value::Number(0),
-                            }
-                        }
-                        return Value::Number(0);
-                    }
-                    "matn" => {
-                        if let Some(val) = arg_values.first() {
-                            return Value::String(Rc::from(val.to_string()));
-                        }
-                        return Value::empty_string();
-                    }
-                    "turi" => {
-                        if let Some(val) = arg_values.first() {
-                            match val {
-                                Value::Number(_) => return Value::String(Rc::from("son")),
-                                Value::String(_) => return Value::String(Rc::from("matn")),
-                                Value::Bool(_) => return Value::String(Rc::from("mantiq")),
-                                Value::Array(_) => return Value::String(Rc::from("massiv")),
-                            }
-                        }
-                        return Value::String(Rc::from("noma'lum"));
-                    }
-                    "uzunlik" => {
-                        if let Some(val) = arg_values.first() {
-                            if let Value::Array(arr) = val {
-                                return Value::Number(arr.len() as i64);
-                            }
-                        }
-                        return Value::Number(0);
-                    }
-                    "qosh" => {
-                        // qosh(arr, val) -> returns new array
-                        if arg_values.len() >= 2 {
-                            if let Value::Array(rc_arr) = &arg_values[0] {
-                                let mut arr = (**rc_arr).clone();
-                                arr.push(arg_values[1].clone());
-                                return Value::Array(Rc::new(arr));
-                            } else {
-                                eprintln!(
-                                    "Xatolik: 'qosh' funksiyasining birinchi parametri massiv bo'lishi kerak"
-                                );
-                            }
-                        }
-                        return Value::Number(0);
-                    }
-                    "internet_ol" => {
-                        if let Some(val) = arg_values.first() {
-                            let url = val.to_string();
-
-                            if !is_safe_url(&url) {
-                                eprintln!(
-                                    "Xatolik: Xavfsizlik qoidasi buzildi - mahalliy yoki xususiy tarmoqqa ulanish taqiqlangan: {}",
-                                    url
-                                );
-                                return Value::empty_string();
-                            }
-
-                            // Use shared client that does not follow redirects for security
-                            match self.client.get(&url).send() {
-                                Ok(resp) => {
-                                    let mut buffer = String::new();
-                                    if resp
-                                        .take(MAX_RESPONSE_SIZE)
-                                        .read_to_string(&mut buffer)
-                                        .is_err()
-                                    {
-                                        eprintln!("Xatolik: Javobni o'qishda xatolik");
-                                        return Value::empty_string();
-                                    }
-                                    return Value::String(Rc::from(buffer));
-                                }
-                                Err(e) => {
-                                    eprintln!("Xatolik: Internet so'rovida xatolik: {}", e);
-                                    return Value::empty_string();
-                                }
-                            }
-                        }
-                        return Value::empty_string();
-                    }
-                    "internet_yoz" => {
-                        if arg_values.len() >= 2 {
-                            let url_str = arg_values[0].to_string();
-                            let json_data = arg_values[1].to_string();
-
-                            if !is_safe_url(&url) {
-                                eprintln!(
-                                    "Xatolik: Xavfsizlik qoidasi buzildi - mahalliy yoki xususiy tarmoqqa ulanish taqiqlangan: {}",
-                                    url
-                                );
-                                return Value::empty_string();
-                            }
-
-                            // Use shared client that does not follow redirects for security
-                            match self
-                                .client
-                                .post(&url)
-                                .header("Content-Type", "application/json")
-                                .body(json_data)
-                                .send()
-                            {
-                                Ok(resp) => {
-                                    let mut buffer = String::new();
-                                    if resp
-                                        .take(MAX_RESPONSE_SIZE)
-                                        .read_to_string(&mut buffer)
-                                        .is_err()
-                                    {
-                                        eprintln!("Xatolik: Javobni o'qishda xatolik");
-                                        return Value::empty_string();
-                                    }
-                                    return Value::String(Rc::from(buffer));
-                                }
-                                Err(e) => {
-                                    eprintln!("Xatolik: Internet so'rovida xatolik: {}", e);
-                                    return Value::empty_string();
-                                }
-                            }
-                        }
-                        return Value::empty_string();
+                // User functions and natives are ordinary values
+                let callee_val = match (callee_val, &callee.kind) {
+                    (Some(val), _) => val,
+                    (None, ExprKind::Identifier(name)) => self.lookup(name).ok_or_else(|| {
+                        RuntimeError::new(
+                            RuntimeErrorKind::UnknownFunction(name.clone()),
+                            callee.span,
+                        )
+                    })?,
+                    (None, _) => unreachable!("only identifiers are resolved by name"),
+                };
+                match callee_val {
+                    Value::Function(func) => self.call_function(&func, arg_values, expr.span),
+                    Value::Native(native) => {
+                        let arg_spans: Vec<Span> = args.iter().map(|a| a.span).collect();
+                        self.call_native(&native, arg_values, &arg_spans, expr.span)
                     }
-                    _ => {}
+                    _ => Err(RuntimeError::type_mismatch(
+                        format!(
+                            "{} funksiya emas, uni chaqirib bo'lmaydi",
+                            callee_val.type_name()
+                        ),
+                        callee.span,
+                    )),
                 }
+            }
+            ExprKind::UnaryOp(UnOp::Not, right) => {
+                let val = self.evaluate(right)?;
+                Ok(Value::Bool(!self.is_truthy(val)))
+            }
+            ExprKind::BinaryOp(left, BinOp::And, right) => {
+                let l = self.evaluate(left)?;
+                if !self.is_truthy(l) {
+                    return Ok(Value::Bool(false));
+                }
+                let r = self.evaluate(right)?;
+                Ok(Value::Bool(self.is_truthy(r)))
+            }
+            ExprKind::BinaryOp(left, BinOp::Or, right) => {
+                let l = self.evaluate(left)?;
+                if self.is_truthy(l) {
+                    return Ok(Value::Bool(true));
+                }
+                let r = self.evaluate(right)?;
+                Ok(Value::Bool(self.is_truthy(r)))
+            }
+            ExprKind::BinaryOp(left, op, right) => {
+                let l = self.evaluate(left)?;
+                let r = self.evaluate(right)?;
+                self.evaluate_binary(l, *op, r, expr.span)
+            }
+        }
+    }
 
-                // User functions
-                if let Some((params, body)) = self.functions.get(name) {
-                    let params = Rc::clone(params);
-                    let body = Rc::clone(body);
-
-                    // Create new scope
-                    // Bolt: Pre-allocate HashMap capacity to avoid reallocation for function scopes
-                    let mut scope = HashMap::with_capacity(params.len());
-                    for (i, param) in params.iter().enumerate() {
-                        if let Some(val) = arg_values.get(i) {
-                            scope.insert(param.clone(), val.clone());
-                        } else {
-                            // Default value for missing args?
-                            scope.insert(param.clone(), Value::Number(0));
-                        }
-                    }
+    pub(crate) fn evaluate_binary(
+        &self,
+        left: Value,
+        op: BinOp,
+        right: Value,
+        span: Span,
+    ) -> EvalResult {
+        // Mixed integer/float operands are promoted to f64
+        let floats = match (&left, &right) {
+            (Value::Float(l), Value::Float(r)) => Some((*l, *r)),
+            (Value::Float(l), Value::Number(r)) => Some((*l, *r as f64)),
+            (Value::Number(l), Value::Float(r)) => Some((*l as f64, *r)),
+            _ => None,
+        };
+
+        if let BinOp::Eq | BinOp::NotEq = op {
+            let equal = values_equal(&left, &right);
+            return Ok(Value::Bool(equal == (op == BinOp::Eq)));
+        }
 
-                    self.env_stack.push(scope);
-                    let result = self.execute(&body);
-                    self.env_stack.pop();
+        let mismatch = |left: &Value, right: &Value| {
+            RuntimeError::type_mismatch(
+                format!(
+                    "'{}' amalini {} va {} uchun bajarib bo'lmaydi",
+                    op.symbol(),
+                    left.type_name(),
+                    right.type_name()
+                ),
+                span,
+            )
+        };
 
-                    return result.unwrap_or(Value::Number(0)); // Default return 0
-                }
+        if let Some((l, r)) = floats {
+            return match op {
+                BinOp::Add => Ok(Value::Float(l + r)),
+                BinOp::Sub => Ok(Value::Float(l - r)),
+                BinOp::Mul => Ok(Value::Float(l * r)),
+                BinOp::Div => Ok(Value::Float(l / r)),
+                BinOp::Mod => Ok(Value::Float(l % r)),
+                BinOp::Pow => Ok(Value::Float(l.powf(r))),
+                BinOp::Gt => Ok(Value::Bool(l > r)),
+                BinOp::Lt => Ok(Value::Bool(l < r)),
+                BinOp::GtEq => Ok(Value::Bool(l >= r)),
+                BinOp::LtEq => Ok(Value::Bool(l <= r)),
+                _ => Err(mismatch(&left, &right)),
+            };
+        }
 
-                eprintln!("Xatolik: Funksiya topilmadi: {}", name);
-                Value::Number(0)
-            }
-            Expr::UnaryOp(op, right) => {
-                let val = self.evaluate(right);
-                match op.as_str() {
-                    "!" => Value::Bool(!self.is_truthy(val)),
-                    _ => Value::Bool(false),
+        match (&left, &right) {
+            (Value::Number(l), Value::Number(r)) => {
+                let (l, r) = (*l, *r);
+                match op {
+                    BinOp::Add => checked(l.checked_add(r), span),
+                    BinOp::Sub => checked(l.checked_sub(r), span),
+                    BinOp::Mul => checked(l.checked_mul(r), span),
+                    BinOp::Div | BinOp::Mod if r == 0 => {
+                        Err(RuntimeError::arithmetic(DIVISION_BY_ZERO, span))
+                    }
+                    // Only i64::MIN / -1 can overflow here
+                    BinOp::Div => checked(l.checked_div(r), span),
+                    // The remainder has the sign of `l`, matching `/` which rounds toward zero
+                    BinOp::Mod => Ok(Value::Number(l.wrapping_rem(r))),
+                    BinOp::Pow => integer_power(l, r, span),
+                    BinOp::Gt => Ok(Value::Bool(l > r)),
+                    BinOp::Lt => Ok(Value::Bool(l < r)),
+                    BinOp::GtEq => Ok(Value::Bool(l >= r)),
+                    BinOp::LtEq => Ok(Value::Bool(l <= r)),
+                    _ => Err(mismatch(&left, &right)),
                 }
             }
-            Expr::BinaryOp(left, op, right) => {
-                if op == "&&" {
-                    let l = self.evaluate(left);
-                    if !self.is_truthy(l) {
-                        return Value::Bool(false);
-                    }
-                    let r = self.evaluate(right);
-                    return Value::Bool(self.is_truthy(r));
+            (Value::String(l), Value::String(r)) if op == BinOp::Add => {
+                if l.is_empty() {
+                    return Ok(right);
                 }
-                if op == "||" {
-                    let l = self.evaluate(left);
-                    if self.is_truthy(l) {
-                        return Value::Bool(true);
-                    }
-                    let r = self.evaluate(right);
-                    return Value::Bool(self.is_truthy(r));
+                if r.is_empty() {
+                    return Ok(left);
                 }
-                let l = self.evaluate(left);
-                let r = self.evaluate(right);
-                self.evaluate_binary(l, op, r)
-            }
-        }
-    }
-
-    fn evaluate_binary(&self, left: Value, op: &str, right: Value) -> Value {
-        match (left, right) {
-            (Value::Number(l), Value::Number(r)) => match op {
-                "+" => Value::Number(l + r),
-                "-" => Value::Number(l - r),
-                "*" => Value::Number(l * r),
-                "/" => Value::Number(l / r),
-                "==" => Value::Bool(l == r),
-                "!=" => Value::Bool(l != r),
-                ">" => Value::Bool(l > r),
-                "<" => Value::Bool(l < r),
-                ">=" => Value::Bool(l >= r),
-                "<=" => Value::Bool(l <= r),
-                _ => Value::Bool(false),
-            },
-            (Value::String(l), Value::String(r)) => match op {
-                "+" => {
-                    if l.is_empty() { return Value::String(r); }
-                    if r.is_empty() { return Value::String(l); }
-                    let mut new_str = String::with_capacity(l.len() + r.len());
-                    new_str.push_str(&l);
-                    new_str.push_str(&r);
-                    Value::String(Rc::from(new_str))
-                },
-                "==" => Value::Bool(l == r),
-                "!=" => Value::Bool(l != r),
-                _ => Value::Bool(false),
-            },
-            (Value::String(l), Value::Number(r)) => match op {
-                "+" => {
-                    let r_str = r.to_string();
-                    let mut new_str = String::with_capacity(l.len() + r_str.len());
-                    new_str.push_str(&l);
-                    new_str.push_str(&r_str);
-                    Value::String(Rc::from(new_str))
-                },
-                _ => Value::Bool(false),
-            },
-            (Value::Number(l), Value::String(r)) => match op {
-                "+" => {
-                    let l_str = l.to_string();
-                    let mut new_str = String::with_capacity(l_str.len() + r.len());
-                    new_str.push_str(&l_str);
-                    new_str.push_str(&r);
-                    Value::String(Rc::from(new_str))
-                },
-                _ => Value::Bool(false),
-            },
-            _ => Value::Bool(false),
+                let mut new_str = String::with_capacity(l.len() + r.len());
+                new_str.push_str(l);
+                new_str.push_str(r);
+                self.sized(Value::String(Rc::from(new_str)), span)
+            }
+            (Value::String(l), Value::Number(_) | Value::Float(_)) if op == BinOp::Add => {
+                let r_str = right.to_string();
+                let mut new_str = String::with_capacity(l.len() + r_str.len());
+                new_str.push_str(l);
+                new_str.push_str(&r_str);
+                self.sized(Value::String(Rc::from(new_str)), span)
+            }
+            (Value::Number(_) | Value::Float(_), Value::String(r)) if op == BinOp::Add => {
+                let l_str = left.to_string();
+                let mut new_str = String::with_capacity(l_str.len() + r.len());
+                new_str.push_str(&l_str);
+                new_str.push_str(r);
+                self.sized(Value::String(Rc::from(new_str)), span)
+            }
+            _ => Err(mismatch(&left, &right)),
         }
     }
 
-    fn is_truthy(&self, val: Value) -> bool {
+    pub(crate) fn is_truthy(&self, val: Value) -> bool {
         match val {
             Value::Bool(b) => b,
             Value::Number(n) => n != 0,
+            Value::Float(n) => n != 0.0,
             _ => false,
         }
     }
 }
 
+/// Modullar testlari uchun: dasturni ikkala dvigatelda bajarib, natijalarini solishtiradi.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Tree and bytecode interpreters, each configured by `setup`.
+    pub(crate) fn both_engines(mut setup: impl FnMut(&mut Interpreter)) -> [Interpreter; 2] {
+        [Engine::Tree, Engine::Bytecode].map(|engine| {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_engine(engine);
+            setup(&mut interpreter);
+            interpreter
+        })
+    }
+
+    fn execute(interpreter: &mut Interpreter, code: &str) -> Result<(), RuntimeError> {
+        interpreter.run_source(code).map_err(|err| match err {
+            crate::Error::Runtime(err) => err,
+            crate::Error::Syntax(d) => panic!("sintaksis xatosi: {:?}", d),
+        })?;
+        Ok(())
+    }
+
+    fn globals_text(interpreter: &Interpreter) -> Vec<String> {
+        interpreter
+            .globals()
+            .iter()
+            .map(|(name, val)| format!("{} = {}", name, val))
+            .collect()
+    }
+
+    /// Runs the program on both engines, each configured by `setup`, and checks
+    /// that globals, warnings and errors agree. Returns the VM interpreter.
+    pub(crate) fn run_configured(
+        code: &str,
+        setup: impl FnMut(&mut Interpreter),
+    ) -> Result<Interpreter, RuntimeError> {
+        let [mut tree, mut vm] = both_engines(setup);
+        let tree_result = execute(&mut tree, code);
+        let vm_result = execute(&mut vm, code);
+        match (&tree_result, &vm_result) {
+            (Ok(()), Ok(())) => {
+                assert_eq!(globals_text(&tree), globals_text(&vm));
+                assert_eq!(tree.diagnostics, vm.diagnostics);
+            }
+            (Err(tree), Err(vm)) => assert_eq!(tree, vm),
+            _ => panic!(
+                "dvigatellar natijasi farq qiladi: daraxt {:?}, vm {:?}",
+                tree_result.err(),
+                vm_result.err()
+            ),
+        }
+        vm_result.map(|()| vm)
+    }
+
+    /// Runs the program on both engines with the given limits and checks that they agree.
+    pub(crate) fn run_limited(code: &str, limits: Limits) -> Result<Interpreter, RuntimeError> {
+        run_configured(code, |interpreter| interpreter.set_limits(limits))
+    }
+
+    /// Runs the program on both engines and checks that they agree.
+    pub(crate) fn run(code: &str) -> Result<Interpreter, RuntimeError> {
+        run_limited(code, Limits::default())
+    }
+
+    /// `run` kabi, lekin `javob` o'zgaruvchisining qiymatini qaytaradi.
+    pub(crate) fn answer(code: &str) -> Result<Value, RuntimeError> {
+        run(code).map(|interpreter| {
+            interpreter
+                .get_variable("javob")
+                .expect("'javob' o'zgaruvchisi topilmadi")
+        })
+    }
+
+    /// Creates a fresh folder under the system temp directory holding `files`
+    /// (relative path, content). The caller removes it.
+    pub(crate) fn temp_project(files: &[(&str, &str)]) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "uzlang_test_{}_{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, content) in files {
+            write_file(&dir, name, content);
+        }
+        dir
+    }
+
+    /// Writes `content` to `dir/name`, creating parent folders.
+    fn write_file(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::test_support::{both_engines, run};
     use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn run_err(code: &str) -> RuntimeError {
+        match run(code) {
+            Ok(_) => panic!("xato kutilgan edi"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn test_runtime_error_has_span() {
+        let err = run_err("x = [1, 2]\ny = x[5]");
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::IndexOutOfBounds { index: 5, len: 2 }
+        );
+        assert_eq!(err.to_diagnostic().code, "R004");
+        assert_eq!(err.span, Span::new(2, 7, 1));
+    }
 
     #[test]
-    fn test_is_safe_ip_v4() {
-        assert!(!is_safe_ip("127.0.0.1".parse().unwrap()));
-        assert!(!is_safe_ip("10.0.0.1".parse().unwrap()));
-        assert!(!is_safe_ip("192.168.1.1".parse().unwrap()));
-        assert!(!is_safe_ip("172.16.0.1".parse().unwrap()));
-        assert!(!is_safe_ip("169.254.1.1".parse().unwrap()));
-        assert!(!is_safe_ip("0.0.0.0".parse().unwrap()));
-        assert!(is_safe_ip("8.8.8.8".parse().unwrap()));
-        assert!(is_safe_ip("1.1.1.1".parse().unwrap()));
+    fn test_runtime_error_halts_execution() {
+        let err = run_err("a = 1\nb = yoq + 1\na = 2");
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::UndefinedVariable("yoq".to_string())
+        );
+    }
+
+    #[test]
+    fn test_type_errors() {
+        let err = run_err("x = \"a\" - 1");
+        assert!(matches!(err.kind, RuntimeErrorKind::TypeMismatch(_)));
+        let err = run_err("x = son(\"abc\")");
+        assert!(matches!(err.kind, RuntimeErrorKind::TypeMismatch(_)));
+        let interpreter = run("x = 1 == \"1\"").unwrap();
+        assert_eq!(interpreter.get_variable("x"), Some(Value::Bool(false)));
+
+        // Conversions and collection builtins never fall back to a silent zero
+        for code in [
+            "x = son([1])",
+            "x = son({})",
+            "x = son(son)",
+            "x = uzunlik(5)",
+            "x = uzunlik(rost)",
+            "x = uzunlik(uzunlik)",
+        ] {
+            let err = run_err(code);
+            assert!(
+                matches!(err.kind, RuntimeErrorKind::TypeMismatch(_)),
+                "{}",
+                code
+            );
+            assert_eq!(err.span.column, code.find('(').unwrap() + 2, "{}", code);
+        }
+        assert_eq!(
+            run_err("x = uzunlik(2.5)").kind.to_string(),
+            "'uzunlik' funksiyasining 1-parametri matn, massiv yoki lug'at bo'lishi kerak, haqiqiy berildi"
+        );
+        assert_eq!(
+            run_err("x = qosh(\"ab\", 1)").kind.to_string(),
+            "'qosh' funksiyasining 1-parametri massiv bo'lishi kerak, matn berildi"
+        );
+        assert_eq!(
+            run_err("x = kalitlar([1])").kind.to_string(),
+            "'kalitlar' funksiyasining 1-parametri lug'at bo'lishi kerak, massiv berildi"
+        );
+    }
+
+    #[test]
+    fn test_stack_trace() {
+        let code = "funksiya ichki(n) {\n  qaytar n[0]\n}\nfunksiya tashqi() {\n  qaytar ichki(5)\n}\nx = tashqi()";
+        let err = run_err(code);
+        assert_eq!(err.span, Span::new(2, 10, 1));
+        let names: Vec<&str> = err.trace.iter().map(|f| f.function.as_str()).collect();
+        assert_eq!(names, vec!["ichki", "tashqi"]);
+        assert_eq!(err.trace[0].span.line, 5);
+        assert_eq!(err.trace[1].span.line, 7);
+        assert!(
+            err.render(code, "a.uz")
+                .contains("'ichki' funksiyasida, a.uz:5:10 da chaqirilgan")
+        );
+    }
+
+    #[test]
+    fn test_argument_count() {
+        let err = run_err("funksiya f(a, b) { qaytar a }\nf(1)");
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::ArgumentCount {
+                name: "f".to_string(),
+                expected: 2,
+                given: 1
+            }
+        );
     }
 
     #[test]
-    fn test_is_safe_ip_v6() {
-        assert!(!is_safe_ip("::1".parse().unwrap()));
-        assert!(!is_safe_ip("::".parse().unwrap()));
-        assert!(!is_safe_ip("fc00::1".parse().unwrap()));
-        assert!(!is_safe_ip("fe80::1".parse().unwrap()));
-        assert!(!is_safe_ip("::ffff:127.0.0.1".parse().unwrap()));
-        assert!(is_safe_ip("2001:db8::1".parse().unwrap()));
+    fn test_else_if_runs_first_matching_branch() {
+        let code = "natija = []
+            uchun n ichida [1, 5, 10] {
+                agar n > 7 {
+                    natija = qosh(natija, \"katta\")
+                } yoki agar n > 3 {
+                    natija = qosh(natija, \"o'rta\")
+                } yoki agar n > 0 {
+                    natija = qosh(natija, \"kichik\")
+                } aks holda {
+                    natija = qosh(natija, \"manfiy\")
+                }
+            }";
+        let interpreter = run(code).unwrap();
+        assert_eq!(
+            interpreter.get_variable("natija").unwrap().to_string(),
+            "[kichik; o'rta; katta]"
+        );
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let code = "juftlar = []
+            i = 0
+            takrorla 1 {
+                i = i + 1
+                agar i > 10 { to'xta }
+                agar i == 4 { davom et }
+                uchun j ichida [0, 1] {
+                    agar j == 1 { to'xta }
+                    juftlar = qosh(juftlar, i)
+                }
+            }";
+        let interpreter = run(code).unwrap();
+        assert_eq!(
+            interpreter.get_variable("juftlar").unwrap().to_string(),
+            "[1; 2; 3; 5; 6; 7; 8; 9; 10]"
+        );
+    }
+
+    #[test]
+    fn test_return_from_nested_loops() {
+        let code = "funksiya top(massiv, x) {
+                uchun qator ichida massiv {
+                    uchun y ichida qator {
+                        agar y == x { qaytar 1 }
+                    }
+                }
+                qaytar 0
+            }
+            a = top([[1, 2], [3, 4]], 3)
+            b = top([[1, 2]], 9)";
+        let interpreter = run(code).unwrap();
+        assert_eq!(interpreter.get_variable("a"), Some(Value::Number(1)));
+        assert_eq!(interpreter.get_variable("b"), Some(Value::Number(0)));
+    }
+
+    #[test]
+    fn test_float_arithmetic_and_display() {
+        let code = "o'rtacha = haqiqiy(7) / 2
+            butun = 7 / 2
+            aralash = 1 + 0.5
+            teng = 2 == 2.0
+            matni = \"natija: \" + o'rtacha
+            qaytgan = haqiqiy(\"2,25\") + son(3.9)";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap();
+        assert_eq!(get("o'rtacha"), Value::Float(3.5));
+        assert_eq!(get("butun"), Value::Number(3));
+        assert_eq!(get("aralash"), Value::Float(1.5));
+        assert_eq!(get("teng"), Value::Bool(true));
+        assert_eq!(get("matni").to_string(), "natija: 3,5");
+        assert_eq!(get("qaytgan"), Value::Float(5.25));
+    }
+
+    #[test]
+    fn test_collection_equality_promotes_numbers() {
+        let code = "a = [1 == 1.0, [1, 2] == [1.0, 2], [[3]] == [[3.0]], [1] != [1.0]]
+            b = [{\"x\": 1} == {\"x\": 1.0}, {\"x\": [2]} == {\"x\": [2.0]}]
+            c = [[1] == [1.5], [1] == [1, 1], {\"x\": 1} == {\"y\": 1}, [1] == [\"1\"]]";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("a"), "[rost; rost; rost; yolg'on]");
+        assert_eq!(get("b"), "[rost; rost]");
+        assert_eq!(get("c"), "[yolg'on; yolg'on; yolg'on; yolg'on]");
+    }
+
+    #[test]
+    fn test_float_formatting() {
+        assert_eq!(Value::Float(2.0).to_string(), "2,0");
+        assert_eq!(Value::Float(-0.125).to_string(), "-0,125");
+        assert_eq!(Value::Float(1.0 / 0.0).to_string(), "cheksiz");
+        assert_eq!(Value::Float(0.1 + 0.2).to_string(), "0,30000000000000004");
+
+        // The decimal comma must not read as a second element
+        let interpreter = run("a = [1.5, 2.0, 3]\nd = {\"x\": 1.5, \"y\": [0.25]}").unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("a"), "[1,5; 2,0; 3]");
+        assert_eq!(get("d"), "{x: 1,5; y: [0,25]}");
+    }
+
+    #[test]
+    fn test_dict_word_count() {
+        let code = "sanoq = {}
+            uchun soz ichida [\"olma\", \"nok\", \"olma\"] {
+                agar bormi(sanoq, soz) {
+                    sanoq[soz] = sanoq[soz] + 1
+                } aks holda {
+                    sanoq[soz] = 1
+                }
+            }
+            kalitlar_soni = 0
+            uchun k ichida sanoq {
+                kalitlar_soni = kalitlar_soni + 1
+            }";
+        let interpreter = run(code).unwrap();
+        assert_eq!(
+            interpreter.get_variable("sanoq").unwrap().to_string(),
+            "{nok: 1; olma: 2}"
+        );
+        assert_eq!(
+            interpreter.get_variable("kalitlar_soni"),
+            Some(Value::Number(2))
+        );
+    }
+
+    #[test]
+    fn test_dict_literal_and_copy_on_write() {
+        let code = "a = {\"ism\": \"Ali\", 1: [2, 3]}
+            b = a
+            b[\"ism\"] = \"Vali\"
+            ism = a[\"ism\"]
+            k = kalitlar(b)
+            q = qiymatlar(a)
+            n = uzunlik(a)";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("ism"), "Ali");
+        assert_eq!(get("b"), "{1: [2; 3]; ism: Vali}");
+        assert_eq!(get("k"), "[1; ism]");
+        assert_eq!(get("q"), "[[2; 3]; Ali]");
+        assert_eq!(get("n"), "2");
+    }
+
+    #[test]
+    fn test_dict_missing_key() {
+        let err = run_err("a = {\"x\": 1}\nb = a[\"y\"]");
+        assert_eq!(err.kind, RuntimeErrorKind::KeyNotFound("y".to_string()));
+        let err = run_err("a = {[1]: 1}");
+        assert!(matches!(err.kind, RuntimeErrorKind::TypeMismatch(_)));
+    }
+
+    #[test]
+    fn test_closure_captures_environment() {
+        let code = "funksiya qoshuvchi(n) {
+                qaytar funksiya(x) { qaytar x + n }
+            }
+            besh = qoshuvchi(5)
+            a = besh(1)
+            b = qoshuvchi(10)(2)
+            n = 100
+            c = besh(1)";
+        let interpreter = run(code).unwrap();
+        assert_eq!(interpreter.get_variable("a"), Some(Value::Number(6)));
+        assert_eq!(interpreter.get_variable("b"), Some(Value::Number(12)));
+        assert_eq!(interpreter.get_variable("c"), Some(Value::Number(6)));
+    }
+
+    #[test]
+    fn test_functions_as_values() {
+        let code = "funksiya xarita(arr, f) {
+                natija = []
+                uchun x ichida arr { natija = qosh(natija, f(x)) }
+                qaytar natija
+            }
+            funksiya kvadrat(x) { qaytar x * x }
+            a = xarita([1, 2, 3], kvadrat)
+            b = xarita([1, 2], funksiya(x) { qaytar x + 1 })
+            amallar = [kvadrat, funksiya(x) { qaytar 0 - x }]
+            c = amallar[1](4)
+            t = turi(kvadrat)
+            m = matn(kvadrat)";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("a"), "[1; 4; 9]");
+        assert_eq!(get("b"), "[2; 3]");
+        assert_eq!(get("c"), "-4");
+        assert_eq!(get("t"), "funksiya");
+        assert_eq!(get("m"), "<funksiya kvadrat>");
+    }
+
+    #[test]
+    fn test_local_function_recursion() {
+        let code = "funksiya hisobla(n) {
+                funksiya fakt(k) {
+                    agar k < 2 { qaytar 1 }
+                    qaytar k * fakt(k - 1)
+                }
+                qaytar fakt(n)
+            }
+            x = hisobla(5)";
+        let interpreter = run(code).unwrap();
+        assert_eq!(interpreter.get_variable("x"), Some(Value::Number(120)));
+    }
+
+    #[test]
+    fn test_callee_does_not_see_caller_locals() {
+        let code = "funksiya ichki() { qaytar yashirin }
+            funksiya tashqi() {
+                yashirin = 1
+                qaytar ichki()
+            }
+            x = tashqi()";
+        let err = run_err(code);
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::UndefinedVariable("yashirin".to_string())
+        );
+        let err = run_err(
+            "x = 5
+y = x(1)",
+        );
+        assert!(matches!(err.kind, RuntimeErrorKind::TypeMismatch(_)));
+    }
+
+    #[test]
+    fn test_cyrillic_program() {
+        let code = "функция иккилан(сон) { қайтар сон * 2 }
+            натижа = []
+            учун x ичида [1, 2, 3] {
+                агар x == 2 { давом эт }
+                натижа = qosh(натижа, иккилан(x))
+            }
+            o‘quvchi = 1
+            o'quvchi = o`quvchi + 1";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("натижа"), "[2; 6]");
+        assert_eq!(get("o'quvchi"), "2");
+    }
+
+    #[test]
+    fn test_for_loop_scopes() {
+        let code = "x = 10
+            natija = []
+            uchun x ichida [1, 2] {
+                y = x * 2
+                natija = qosh(natija, y)
+            }
+            funksiya f(i) {
+                jami = 0
+                uchun i ichida [1, 2, 3, 4] {
+                    agar i == 2 { davom et }
+                    agar i == 4 { to'xta }
+                    ichki = i
+                    jami = jami + ichki
+                }
+                qaytar [i, jami]
+            }
+            s = f(7)
+            fs = []
+            uchun i ichida [1, 2, 3] {
+                fs = qosh(fs, funksiya() { qaytar i })
+            }
+            c = [fs[0](), fs[2]()]";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("x"), "10");
+        assert_eq!(get("natija"), "[2; 4]");
+        assert_eq!(get("s"), "[7; 4]");
+        assert_eq!(get("c"), "[1; 3]");
+        assert_eq!(interpreter.get_variable("y"), None);
+
+        let err = run_err(
+            "funksiya f() {
+ uchun i ichida [1] { y = 1 }
+ qaytar y
+}
+f()",
+        );
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::UndefinedVariable("y".to_string())
+        );
+        assert_eq!(err.span, Span::new(3, 9, 1));
+    }
+
+    #[test]
+    fn test_boolean_literals() {
+        let code = "a = rost
+            b = !a == yolg'on
+            c = [rost, ёлғон, 1 > 2]
+            d = [matn(rost) + \"!\", son(rost), haqiqiy(yolg'on), turi(рост)]
+            e = [mantiq(\"rost\"), mantiq(\" yolg‘on \"), mantiq(0), mantiq(2.5)]
+            agar yolg'on { f = 1 } aks holda { f = 2 }";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("a"), "rost");
+        assert_eq!(get("b"), "rost");
+        assert_eq!(get("c"), "[rost; yolg'on; yolg'on]");
+        assert_eq!(get("d"), "[rost!; 1; 0,0; mantiq]");
+        assert_eq!(get("e"), "[rost; yolg'on; yolg'on; rost]");
+        assert_eq!(get("f"), "2");
+
+        let err = run_err("x = mantiq(\"ha\")");
+        assert_eq!(err.kind.code(), "R003");
+    }
+
+    #[test]
+    fn test_try_catches_errors() {
+        let code = "a = [1, 2]
+            urinib ko'r {
+                x = a[5]
+                yoz \"yetib kelmaydi\"
+            } xato bo'lsa e {
+                xabar = e[\"xabar\"]
+                tur = e[\"turi\"]
+                qator = e[\"qator\"]
+            }
+            funksiya tekshir(n) {
+                agar n < 0 { xato_chiqar(\"manfiy son: \" + matn(n)) }
+                qaytar n
+            }
+            urinib ko'r { tekshir(0 - 3) } xato bo'lsa e { o = e }
+            urinib ko'r { tekshir(3) } xato bo'lsa { yoz 1 }
+            urinib ko'r { son(\"abc\") } xato bo'lsa { keyin = \"davom etdi\" }";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("xabar"), "Indeks chegaradan tashqarida: 5 (uzunlik 2)");
+        assert_eq!(get("tur"), "R004");
+        assert_eq!(get("qator"), "3");
+        assert_eq!(get("o"), "{qator: 11; turi: R011; xabar: manfiy son: -3}");
+        assert_eq!(get("keyin"), "davom etdi");
+
+        // An error in the handler, or one left uncaught, stops the program
+        let err = run_err(
+            "urinib ko'r { xato_chiqar(1) } xato bo'lsa e {\n xato_chiqar(e[\"xabar\"] + \"!\")\n}",
+        );
+        assert_eq!(err.kind, RuntimeErrorKind::Raised("1!".to_string()));
+        assert_eq!(err.span.line, 2);
+        let err = run_err("funksiya f() { xato_chiqar(\"yo'q\") }\nf()");
+        assert_eq!(err.kind.code(), "R011");
+        assert_eq!(err.trace.len(), 1);
+    }
+
+    #[test]
+    fn test_try_unwinds_loops_and_calls() {
+        let code = "i = 0
+            funksiya f(n) {
+                uchun i ichida [1, 2, 3] {
+                    ichki = i
+                    agar i == n { xato_chiqar(i) }
+                }
+                qaytar 0
+            }
+            natija = []
+            uchun k ichida [1, 2, 3] {
+                urinib ko'r {
+                    agar k == 2 { davom et }
+                    uchun j ichida [10, 20] {
+                        agar k == 3 { to'xta }
+                        f(j / 10)
+                    }
+                } xato bo'lsa e {
+                    natija = qosh(natija, e[\"xabar\"])
+                }
+            }
+            urinib ko'r {
+                takrorla 1 {
+                    urinib ko'r { to'xta } xato bo'lsa { }
+                }
+                x = f(2) + [1][3]
+            } xato bo'lsa e { oxirgi = e[\"xabar\"] }";
+        let interpreter = run(code).unwrap();
+        let get = |name: &str| interpreter.get_variable(name).unwrap().to_string();
+        assert_eq!(get("natija"), "[1]");
+        assert_eq!(get("oxirgi"), "2");
+        assert_eq!(get("i"), "0");
+        assert_eq!(interpreter.get_variable("ichki"), None);
+
+        // Leaving a try block with `to'xta` must not leave its handler active
+        let err =
+            run_err("uchun i ichida [1] {\n urinib ko'r { to'xta } xato bo'lsa { }\n}\nx = [][0]");
+        assert_eq!(err.span.line, 4);
+    }
+
+    #[test]
+    fn test_natives_are_values_and_can_be_shadowed() {
+        let interpreter = run("f = uzunlik\na = f([1, 2])\nb = turi(matn)\nc = matn").unwrap();
+        assert_eq!(interpreter.get_variable("a"), Some(Value::Number(2)));
+        assert_eq!(
+            interpreter.get_variable("b").unwrap().to_string(),
+            "funksiya"
+        );
+        assert_eq!(
+            interpreter.get_variable("c").unwrap().to_string(),
+            "<funksiya matn>"
+        );
+
+        // A user definition with a builtin's name wins over the builtin
+        let interpreter = run("funksiya son(x) { qaytar x + 1 }\na = son(1)").unwrap();
+        assert_eq!(interpreter.get_variable("a"), Some(Value::Number(2)));
+        let interpreter = run("funksiya f(turi) { qaytar turi }\na = f(3)\nb = turi(a)").unwrap();
+        assert_eq!(interpreter.get_variable("b").unwrap().to_string(), "son");
+    }
+
+    #[test]
+    fn test_native_arity_is_checked() {
+        let err = run_err("x = qosh([1])");
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::ArgumentCount {
+                name: "qosh".to_string(),
+                expected: 2,
+                given: 1,
+            }
+        );
+        assert_eq!(err.span, Span::new(1, 5, 9));
+    }
+
+    #[test]
+    fn test_register_native() {
+        for mut interpreter in both_engines(|_| {}) {
+            interpreter.register_native(
+                "takrorla_matn",
+                2,
+                "takrorla_matn(matn, n): matnni n marta takrorlaydi",
+                |_, args| {
+                    let text = args.string(0)?;
+                    let Value::Number(n) = args.get(1) else {
+                        return Err(RuntimeError::type_mismatch(
+                            "n son bo'lishi kerak",
+                            args.span(1),
+                        ));
+                    };
+                    Ok(Value::String(Rc::from(text.repeat(*n as usize))))
+                },
+            );
+            let native = interpreter.native("takrorla_matn").unwrap();
+            assert_eq!(native.arity, 2);
+            assert!(native.doc.starts_with("takrorla_matn(matn, n)"));
+
+            let ast = Parser::new(
+                Lexer::new("a = takrorla_matn(\"ab\", 3)\nb = takrorla_matn(1, 2)").tokenize(),
+            )
+            .parse();
+            let err = interpreter.execute(&ast).unwrap_err();
+            assert_eq!(interpreter.get_variable("a").unwrap().to_string(), "ababab");
+            assert_eq!(err.kind.code(), "R003");
+            assert_eq!(err.span, Span::new(2, 19, 1));
+        }
     }
 }
@@ -1,34 +1,128 @@
+use crate::diagnostic::{Diagnostic, Span};
+use std::fmt;
+
 #[derive(Debug, PartialEq, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Token {
-    Agar,     // agar
-    Toki,     // toki (while)
-    Yoz,      // yoz
-    Takrorla, // takrorla
-    Sora,     // so'ra
-    Funksiya, // funksiya
-    Qaytar,   // qaytar
-    Uchun,    // uchun (for)
-    Ichida,   // ichida (in)
-    And,      // &&
-    Or,       // ||
-    Not,      // !
-    LBrace,   // {
-    RBrace,   // }
-    LParen,   // (
-    RParen,   // )
-    LBracket, // [
-    RBracket, // ]
-    Comma,    // ,
+    Agar,      // agar
+    Toki,      // toki (while)
+    Yoz,       // yoz
+    Takrorla,  // takrorla
+    Sora,      // so'ra
+    Funksiya,  // funksiya
+    Qaytar,    // qaytar
+    Uchun,     // uchun (for)
+    Ichida,    // ichida (in)
+    AksHolda,  // aks holda (else)
+    YokiAgar,  // yoki agar (else if)
+    Toxta,     // to'xta (break)
+    DavomEt,   // davom et (continue)
+    Ulash,     // ulash (import)
+    Sifatida,  // sifatida (as)
+    UrinibKor, // urinib ko'r (try)
+    XatoBolsa, // xato bo'lsa (catch)
+    Rost,      // rost (true)
+    Yolgon,    // yolg'on (false)
+    And,       // &&
+    Or,        // ||
+    Not,       // !
+    Assign,    // =
+    EqEq,      // ==
+    NotEq,     // !=
+    Greater,   // >
+    Less,      // <
+    GreaterEq, // >=
+    LessEq,    // <=
+    Plus,      // +
+    Minus,     // -
+    Star,      // *
+    Slash,     // /
+    Percent,   // %
+    Power,     // **
+    LBrace,    // {
+    RBrace,    // }
+    LParen,    // (
+    RParen,    // )
+    LBracket,  // [
+    RBracket,  // ]
+    Comma,     // ,
+    Colon,     // :
+    Dot,       // .
     Identifier(String),
     Number(i64),
+    Float(f64),
     StringLiteral(String),
-    Operator(String), // ==, >, <, +, -, *, / etc.
     EOF,
 }
 
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Agar => write!(f, "'agar'"),
+            Token::Toki => write!(f, "'toki'"),
+            Token::Yoz => write!(f, "'yoz'"),
+            Token::Takrorla => write!(f, "'takrorla'"),
+            Token::Sora => write!(f, "'so'ra'"),
+            Token::Funksiya => write!(f, "'funksiya'"),
+            Token::Qaytar => write!(f, "'qaytar'"),
+            Token::Uchun => write!(f, "'uchun'"),
+            Token::Ichida => write!(f, "'ichida'"),
+            Token::AksHolda => write!(f, "'aks holda'"),
+            Token::YokiAgar => write!(f, "'yoki agar'"),
+            Token::Toxta => write!(f, "'to'xta'"),
+            Token::DavomEt => write!(f, "'davom et'"),
+            Token::Ulash => write!(f, "'ulash'"),
+            Token::Sifatida => write!(f, "'sifatida'"),
+            Token::UrinibKor => write!(f, "'urinib ko'r'"),
+            Token::XatoBolsa => write!(f, "'xato bo'lsa'"),
+            Token::Rost => write!(f, "'rost'"),
+            Token::Yolgon => write!(f, "'yolg'on'"),
+            Token::And => write!(f, "'&&'"),
+            Token::Or => write!(f, "'||'"),
+            Token::Not => write!(f, "'!'"),
+            Token::Assign => write!(f, "'='"),
+            Token::EqEq => write!(f, "'=='"),
+            Token::NotEq => write!(f, "'!='"),
+            Token::Greater => write!(f, "'>'"),
+            Token::Less => write!(f, "'<'"),
+            Token::GreaterEq => write!(f, "'>='"),
+            Token::LessEq => write!(f, "'<='"),
+            Token::Plus => write!(f, "'+'"),
+            Token::Minus => write!(f, "'-'"),
+            Token::Star => write!(f, "'*'"),
+            Token::Slash => write!(f, "'/'"),
+            Token::Percent => write!(f, "'%'"),
+            Token::Power => write!(f, "'**'"),
+            Token::LBrace => write!(f, "'{{'"),
+            Token::RBrace => write!(f, "'}}'"),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::LBracket => write!(f, "'['"),
+            Token::RBracket => write!(f, "']'"),
+            Token::Comma => write!(f, "','"),
+            Token::Colon => write!(f, "':'"),
+            Token::Dot => write!(f, "'.'"),
+            Token::Identifier(name) => write!(f, "'{}'", name),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Float(n) => write!(f, "{}", n),
+            Token::StringLiteral(s) => write!(f, "\"{}\"", s),
+            Token::EOF => write!(f, "fayl oxiri"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
 pub struct Lexer {
     input: Vec<char>,
     pos: usize,
+    line: usize,
+    line_start: usize,
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl Lexer {
@@ -36,84 +130,137 @@ impl Lexer {
         Lexer {
             input: input.chars().collect(),
             pos: 0,
+            line: 1,
+            line_start: 0,
+            diagnostics: Vec::new(),
         }
     }
 
-    pub fn tokenize(&mut self) -> Vec<Token> {
+    /// Lexer to'plagan xato va ogohlantirishlarni qaytaradi.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    pub fn tokenize(&mut self) -> Vec<SpannedToken> {
         let mut tokens = Vec::new();
         while self.pos < self.input.len() {
-            match self.input[self.pos] {
-                ' ' | '\t' | '\r' | '\n' => {
+            let start = self.pos;
+            let line = self.line;
+            let column = self.pos - self.line_start + 1;
+            let token = match self.input[self.pos] {
+                ' ' | '\t' | '\r' => {
                     self.pos += 1;
+                    continue;
                 }
-                '"' => {
-                    tokens.push(self.read_string());
-                }
-                '0'..='9' => {
-                    tokens.push(self.read_number());
-                }
-                'a'..='z' | 'A'..='Z' | '_' => {
-                    tokens.push(self.read_identifier());
+                '\n' => {
+                    self.pos += 1;
+                    self.line += 1;
+                    self.line_start = self.pos;
+                    continue;
                 }
+                '"' => self.read_string(line, column),
+                '0'..='9' => self.read_number(line, column),
+                c if is_identifier_start(c) => self.read_identifier(),
                 '/' => {
                     if self.pos + 1 < self.input.len() && self.input[self.pos + 1] == '/' {
                         // Skip comment until newline
                         while self.pos < self.input.len() && self.input[self.pos] != '\n' {
                             self.pos += 1;
                         }
-                    } else {
-                        tokens.push(self.read_operator());
+                        continue;
+                    }
+                    match self.read_operator(line, column) {
+                        Some(token) => token,
+                        None => continue,
                     }
                 }
-                '=' | '!' | '>' | '<' | '+' | '-' | '*' | '&' | '|' => {
-                    tokens.push(self.read_operator());
+                '=' | '!' | '>' | '<' | '+' | '-' | '*' | '%' | '&' | '|' => {
+                    match self.read_operator(line, column) {
+                        Some(token) => token,
+                        None => continue,
+                    }
                 }
                 '{' => {
-                    tokens.push(Token::LBrace);
                     self.pos += 1;
+                    Token::LBrace
                 }
                 '}' => {
-                    tokens.push(Token::RBrace);
                     self.pos += 1;
+                    Token::RBrace
                 }
                 '(' => {
-                    tokens.push(Token::LParen);
                     self.pos += 1;
+                    Token::LParen
                 }
                 ')' => {
-                    tokens.push(Token::RParen);
                     self.pos += 1;
+                    Token::RParen
                 }
                 '[' => {
-                    tokens.push(Token::LBracket);
                     self.pos += 1;
+                    Token::LBracket
                 }
                 ']' => {
-                    tokens.push(Token::RBracket);
                     self.pos += 1;
+                    Token::RBracket
                 }
                 ',' => {
-                    tokens.push(Token::Comma);
                     self.pos += 1;
+                    Token::Comma
                 }
-                _ => {
-                    // Unknown character, skip for now
+                ':' => {
                     self.pos += 1;
+                    Token::Colon
                 }
-            }
+                '.' => {
+                    self.pos += 1;
+                    Token::Dot
+                }
+                c => {
+                    let hint = if c == ';' {
+                        " (UzLang'da qator oxiriga ';' qo'yilmaydi)"
+                    } else {
+                        ""
+                    };
+                    self.diagnostics.push(Diagnostic::error(
+                        "L002",
+                        format!("Noma'lum belgi: '{}'{}", c, hint),
+                        Span::new(line, column, 1),
+                    ));
+                    self.pos += 1;
+                    continue;
+                }
+            };
+            let len = if self.line == line {
+                self.pos - start
+            } else {
+                1
+            };
+            tokens.push(SpannedToken {
+                token,
+                span: Span::new(line, column, len),
+            });
         }
 
-        tokens.push(Token::EOF);
+        let column = self.pos - self.line_start + 1;
+        tokens.push(SpannedToken {
+            token: Token::EOF,
+            span: Span::new(self.line, column, 1),
+        });
         tokens
     }
 
-    fn read_string(&mut self) -> Token {
+    fn read_string(&mut self, line: usize, column: usize) -> Token {
         self.pos += 1; // skip opening quote
         let mut s = String::new();
         while self.pos < self.input.len() {
             if self.input[self.pos] == '"' {
                 break;
             }
+            if self.input[self.pos] == '\n' {
+                self.line += 1;
+                self.line_start = self.pos + 1;
+            }
             if self.input[self.pos] == '\\' {
                 self.pos += 1;
                 if self.pos < self.input.len() {
@@ -131,84 +278,185 @@ impl Lexer {
             }
             self.pos += 1;
         }
+        if self.pos >= self.input.len() {
+            self.diagnostics.push(Diagnostic::error(
+                "L001",
+                "Matn yopilmagan: '\"' belgisi kutilgan",
+                Span::new(line, column, 1),
+            ));
+        }
         self.pos += 1; // skip closing quote
         Token::StringLiteral(s)
     }
 
-    fn read_number(&mut self) -> Token {
+    fn read_number(&mut self, line: usize, column: usize) -> Token {
         let mut s = String::new();
         while self.pos < self.input.len() && self.input[self.pos].is_ascii_digit() {
             s.push(self.input[self.pos]);
             self.pos += 1;
         }
-        Token::Number(s.parse().unwrap_or(0))
+        // Haqiqiy son: nuqtadan keyin kamida bitta raqam bo'lishi kerak (3.14)
+        if self.pos + 1 < self.input.len()
+            && self.input[self.pos] == '.'
+            && self.input[self.pos + 1].is_ascii_digit()
+        {
+            s.push('.');
+            self.pos += 1;
+            while self.pos < self.input.len() && self.input[self.pos].is_ascii_digit() {
+                s.push(self.input[self.pos]);
+                self.pos += 1;
+            }
+            return Token::Float(s.parse().unwrap_or(0.0));
+        }
+        s.parse().map(Token::Number).unwrap_or_else(|_| {
+            self.diagnostics.push(Diagnostic::error(
+                "L004",
+                format!("Son juda katta: {} (eng kattasi {})", s, i64::MAX),
+                Span::new(line, column, s.chars().count()),
+            ));
+            Token::Number(0)
+        })
     }
 
     fn read_identifier(&mut self) -> Token {
         let mut s = String::new();
-        while self.pos < self.input.len()
-            && (self.input[self.pos].is_ascii_alphanumeric()
-                || self.input[self.pos] == '_'
-                || self.input[self.pos] == '\'')
-        {
-            s.push(self.input[self.pos]);
+        while self.pos < self.input.len() && is_identifier_char(self.input[self.pos]) {
+            s.push(normalize_apostrophe(self.input[self.pos]));
             self.pos += 1;
         }
 
+        // Kalit so'zlar lotin va kirill yozuvida
         match s.as_str() {
-            "agar" => Token::Agar,
-            "toki" => Token::Toki,
-            "yoz" => Token::Yoz,
-            "takrorla" => Token::Takrorla,
-            "so'ra" => Token::Sora,
-            "funksiya" => Token::Funksiya,
-            "qaytar" => Token::Qaytar,
-            "uchun" => Token::Uchun,
-            "ichida" => Token::Ichida,
+            "aks" if self.consume_following_word("holda") => Token::AksHolda,
+            "акс" if self.consume_following_word("ҳолда") => Token::AksHolda,
+            "yoki" if self.consume_following_word("agar") => Token::YokiAgar,
+            "ёки" if self.consume_following_word("агар") => Token::YokiAgar,
+            "davom" if self.consume_following_word("et") => Token::DavomEt,
+            "давом" if self.consume_following_word("эт") => Token::DavomEt,
+            "urinib" if self.consume_following_word("ko'r") => Token::UrinibKor,
+            "уриниб" if self.consume_following_word("кўр") => Token::UrinibKor,
+            "xato" if self.consume_following_word("bo'lsa") => Token::XatoBolsa,
+            "хато" if self.consume_following_word("бўлса") => Token::XatoBolsa,
+            "to'xta" | "тўхта" => Token::Toxta,
+            "agar" | "агар" => Token::Agar,
+            "toki" | "токи" => Token::Toki,
+            "yoz" | "ёз" => Token::Yoz,
+            "takrorla" | "такрорла" => Token::Takrorla,
+            "so'ra" | "сўра" => Token::Sora,
+            "funksiya" | "функция" => Token::Funksiya,
+            "qaytar" | "қайтар" => Token::Qaytar,
+            "uchun" | "учун" => Token::Uchun,
+            "ichida" | "ичида" => Token::Ichida,
+            "ulash" | "улаш" => Token::Ulash,
+            "sifatida" | "сифатида" => Token::Sifatida,
+            "rost" | "рост" => Token::Rost,
+            "yolg'on" | "ёлғон" => Token::Yolgon,
             _ => Token::Identifier(s),
         }
     }
 
-    fn read_operator(&mut self) -> Token {
-        let mut s = String::new();
-        let current = self.input[self.pos];
-        s.push(current);
-        self.pos += 1;
-
-        if self.pos < self.input.len() {
-            let next = self.input[self.pos];
-            if current == '&' && next == '&' {
-                s.push(next);
-                self.pos += 1;
-                return Token::And;
-            }
-            if current == '|' && next == '|' {
-                s.push(next);
-                self.pos += 1;
-                return Token::Or;
-            }
-            if next == '=' {
-                s.push(next);
-                self.pos += 1;
+    /// Ikki so'zli kalit so'zlar uchun: joriy so'zdan keyin shu qatorda `word` kelsa,
+    /// uni ham iste'mol qiladi.
+    fn consume_following_word(&mut self, word: &str) -> bool {
+        let mut pos = self.pos;
+        while pos < self.input.len() && matches!(self.input[pos], ' ' | '\t') {
+            pos += 1;
+        }
+        if pos == self.pos {
+            return false;
+        }
+        for expected in word.chars() {
+            if self.input.get(pos).map(|&c| normalize_apostrophe(c)) != Some(expected) {
+                return false;
             }
+            pos += 1;
+        }
+        if self.input.get(pos).is_some_and(|&c| is_identifier_char(c)) {
+            return false;
         }
+        self.pos = pos;
+        true
+    }
 
-        match s.as_str() {
-            "!" => Token::Not,
-            _ => Token::Operator(s),
+    /// Amal belgisini o'qiydi. Tilda yo'q amallar (`+=`, `&` kabi) xato sifatida qayd etiladi.
+    fn read_operator(&mut self, line: usize, column: usize) -> Option<Token> {
+        let current = self.input[self.pos];
+        let next = self.input.get(self.pos + 1).copied();
+        let (token, len) = match (current, next) {
+            ('&', Some('&')) => (Some(Token::And), 2),
+            ('|', Some('|')) => (Some(Token::Or), 2),
+            ('=', Some('=')) => (Some(Token::EqEq), 2),
+            ('!', Some('=')) => (Some(Token::NotEq), 2),
+            ('>', Some('=')) => (Some(Token::GreaterEq), 2),
+            ('<', Some('=')) => (Some(Token::LessEq), 2),
+            ('*', Some('*')) => (Some(Token::Power), 2),
+            ('+' | '-' | '*' | '/' | '%' | '&' | '|', Some('=')) => (None, 2),
+            ('=', _) => (Some(Token::Assign), 1),
+            ('!', _) => (Some(Token::Not), 1),
+            ('>', _) => (Some(Token::Greater), 1),
+            ('<', _) => (Some(Token::Less), 1),
+            ('+', _) => (Some(Token::Plus), 1),
+            ('-', _) => (Some(Token::Minus), 1),
+            ('*', _) => (Some(Token::Star), 1),
+            ('/', _) => (Some(Token::Slash), 1),
+            ('%', _) => (Some(Token::Percent), 1),
+            _ => (None, 1),
+        };
+
+        if token.is_none() {
+            let op: String = self.input[self.pos..self.pos + len].iter().collect();
+            let hint = match op.as_str() {
+                "&" => " ('&&' nazarda tutilganmi?)",
+                "|" => " ('||' nazarda tutilganmi?)",
+                _ => "",
+            };
+            self.diagnostics.push(Diagnostic::error(
+                "L003",
+                format!("Noma'lum amal: '{}'{}", op, hint),
+                Span::new(line, column, len),
+            ));
         }
+        self.pos += len;
+        token
     }
 }
 
+/// O‘ va g‘ harflarida ishlatiladigan tutuq belgisi variantlari.
+fn is_apostrophe(c: char) -> bool {
+    matches!(
+        c,
+        '\'' | '`' | '\u{2018}' | '\u{2019}' | '\u{02BB}' | '\u{02BC}'
+    )
+}
+
+/// Nomlarda tutuq belgisining barcha variantlari oddiy `'` ga keltiriladi,
+/// shuning uchun `o‘quvchi` va `o'quvchi` bitta o'zgaruvchi.
+pub(crate) fn normalize_apostrophe(c: char) -> char {
+    if is_apostrophe(c) { '\'' } else { c }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || is_apostrophe(c)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diagnostic::Severity;
+
+    fn kinds(tokens: Vec<SpannedToken>) -> Vec<Token> {
+        tokens.into_iter().map(|t| t.token).collect()
+    }
 
     #[test]
     fn test_tokenize_braces() {
         let input = "takrorla { yoz 1 }";
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = kinds(lexer.tokenize());
         assert_eq!(
             tokens,
             vec![
@@ -229,7 +477,7 @@ mod tests {
             yoz 1
         }";
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = kinds(lexer.tokenize());
         assert_eq!(
             tokens,
             vec![
@@ -247,7 +495,7 @@ mod tests {
     fn test_new_features() {
         let input = "so'ra && || ! // comment";
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = kinds(lexer.tokenize());
         assert_eq!(
             tokens,
             vec![Token::Sora, Token::And, Token::Or, Token::Not, Token::EOF]
@@ -258,24 +506,68 @@ mod tests {
     fn test_operators_mixed() {
         let input = "!= ! == &&";
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = kinds(lexer.tokenize());
         assert_eq!(
             tokens,
             vec![
-                Token::Operator("!=".to_string()),
+                Token::NotEq,
                 Token::Not,
-                Token::Operator("==".to_string()),
+                Token::EqEq,
                 Token::And,
                 Token::EOF
             ]
         );
     }
 
+    #[test]
+    fn test_arithmetic_operators() {
+        let tokens = kinds(Lexer::new("a % 2 ** 3 * 4").tokenize());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier("a".to_string()),
+                Token::Percent,
+                Token::Number(2),
+                Token::Power,
+                Token::Number(3),
+                Token::Star,
+                Token::Number(4),
+                Token::EOF
+            ]
+        );
+        let mut lexer = Lexer::new("a %= 2");
+        lexer.tokenize();
+        assert_eq!(lexer.take_diagnostics()[0].code, "L003");
+    }
+
+    #[test]
+    fn test_oversized_number_is_error() {
+        let mut lexer = Lexer::new("a = 9223372036854775807\nb = 9223372036854775808");
+        let tokens = kinds(lexer.tokenize());
+        assert_eq!(tokens[2], Token::Number(i64::MAX));
+        let diagnostics = lexer.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "L004");
+        assert_eq!(diagnostics[0].span, Span::new(2, 5, 19));
+    }
+
+    #[test]
+    fn test_unknown_operators_are_errors() {
+        let mut lexer = Lexer::new("a += 1\nb = a & 1");
+        let tokens = kinds(lexer.tokenize());
+        assert_eq!(tokens[1], Token::Number(1));
+        let diagnostics = lexer.take_diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code, "L003");
+        assert_eq!(diagnostics[0].span, Span::new(1, 3, 2));
+        assert!(diagnostics[1].message.contains("'&&'"));
+    }
+
     #[test]
     fn test_functions() {
         let input = "funksiya qosh(a, b) { qaytar a + b }";
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = kinds(lexer.tokenize());
         assert_eq!(
             tokens,
             vec![
@@ -289,7 +581,7 @@ mod tests {
                 Token::LBrace,
                 Token::Qaytar,
                 Token::Identifier("a".to_string()),
-                Token::Operator("+".to_string()),
+                Token::Plus,
                 Token::Identifier("b".to_string()),
                 Token::RBrace,
                 Token::EOF
@@ -301,7 +593,7 @@ mod tests {
     fn test_arrays_and_loops() {
         let input = "uchun x ichida [1, 2]";
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = kinds(lexer.tokenize());
         assert_eq!(
             tokens,
             vec![
@@ -317,4 +609,183 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_spans() {
+        let input = "yoz 1\n  x = \"salom\"";
+        let mut lexer = Lexer::new(input);
+        let spans: Vec<Span> = lexer.tokenize().into_iter().map(|t| t.span).collect();
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(1, 1, 3),
+                Span::new(1, 5, 1),
+                Span::new(2, 3, 1),
+                Span::new(2, 5, 1),
+                Span::new(2, 7, 7),
+                Span::new(2, 14, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let mut lexer = Lexer::new("yoz \"salom");
+        lexer.tokenize();
+        let diagnostics = lexer.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "L001");
+        assert_eq!(diagnostics[0].span, Span::new(1, 5, 1));
+    }
+
+    #[test]
+    fn test_two_word_keywords() {
+        let input = "} aks holda {\n} yoki  agar x\naks holdaroq yoki";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[1].span, Span::new(1, 3, 9));
+        assert_eq!(
+            kinds(tokens),
+            vec![
+                Token::RBrace,
+                Token::AksHolda,
+                Token::LBrace,
+                Token::RBrace,
+                Token::YokiAgar,
+                Token::Identifier("x".to_string()),
+                Token::Identifier("aks".to_string()),
+                Token::Identifier("holdaroq".to_string()),
+                Token::Identifier("yoki".to_string()),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loop_control_keywords() {
+        let input = "to'xta davom et davom";
+        let mut lexer = Lexer::new(input);
+        let tokens = kinds(lexer.tokenize());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Toxta,
+                Token::DavomEt,
+                Token::Identifier("davom".to_string()),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_keywords() {
+        let input = "urinib ko‘r { } xato bo'lsa e { }\nxato = 1\nуриниб кўр { } хато бўлса { }";
+        let tokens = kinds(Lexer::new(input).tokenize());
+        assert_eq!(tokens[0], Token::UrinibKor);
+        assert_eq!(tokens[3], Token::XatoBolsa);
+        assert_eq!(tokens[7], Token::Identifier("xato".to_string()));
+        assert_eq!(tokens[10], Token::UrinibKor);
+        assert_eq!(tokens[13], Token::XatoBolsa);
+    }
+
+    #[test]
+    fn test_boolean_literals() {
+        let tokens = kinds(Lexer::new("rost yolg‘on ёлғон рост rosti").tokenize());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Rost,
+                Token::Yolgon,
+                Token::Yolgon,
+                Token::Rost,
+                Token::Identifier("rosti".to_string()),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn test_float_literals() {
+        let input = "2.75 2 0.5 7.";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[0].span, Span::new(1, 1, 4));
+        assert_eq!(
+            kinds(tokens),
+            vec![
+                Token::Float(2.75),
+                Token::Number(2),
+                Token::Float(0.5),
+                Token::Number(7),
+                Token::Dot,
+                Token::EOF
+            ]
+        );
+        assert!(lexer.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_unicode_identifiers_and_apostrophes() {
+        let input = "o‘quvchi gʻallaba o'quvchi sonи сон to’xta";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[0].span, Span::new(1, 1, 8));
+        assert_eq!(
+            kinds(tokens),
+            vec![
+                Token::Identifier("o'quvchi".to_string()),
+                Token::Identifier("g'allaba".to_string()),
+                Token::Identifier("o'quvchi".to_string()),
+                Token::Identifier("sonи".to_string()),
+                Token::Identifier("сон".to_string()),
+                Token::Toxta,
+                Token::EOF
+            ]
+        );
+        assert!(lexer.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_cyrillic_keywords() {
+        let input = "агар x { ёз 1 } ёки агар y { } акс ҳолда { сўра }";
+        let tokens = kinds(Lexer::new(input).tokenize());
+        assert_eq!(tokens[0], Token::Agar);
+        assert_eq!(tokens[3], Token::Yoz);
+        assert_eq!(tokens[6], Token::YokiAgar);
+        assert_eq!(tokens[10], Token::AksHolda);
+        assert_eq!(tokens[12], Token::Sora);
+    }
+
+    #[test]
+    fn test_import_tokens() {
+        let tokens = kinds(Lexer::new("ulash \"m.uz\" sifatida m\nm.f").tokenize());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ulash,
+                Token::StringLiteral("m.uz".to_string()),
+                Token::Sifatida,
+                Token::Identifier("m".to_string()),
+                Token::Identifier("m".to_string()),
+                Token::Dot,
+                Token::Identifier("f".to_string()),
+                Token::EOF
+            ]
+        );
+        let tokens = kinds(Lexer::new("улаш \"m.uz\" сифатида m").tokenize());
+        assert_eq!(tokens[0], Token::Ulash);
+        assert_eq!(tokens[2], Token::Sifatida);
+    }
+
+    #[test]
+    fn test_unknown_character_is_error() {
+        let mut lexer = Lexer::new("yoz 1;\nx = 2 @ 3");
+        lexer.tokenize();
+        let diagnostics = lexer.take_diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+        assert_eq!(diagnostics[0].span, Span::new(1, 6, 1));
+        assert!(diagnostics[0].message.contains("';'"));
+        assert_eq!(diagnostics[1].span, Span::new(2, 7, 1));
+    }
 }
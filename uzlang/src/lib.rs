@@ -0,0 +1,199 @@
+//! UzLang — o'zbek tilida yoziladigan dasturlash tili.
+//!
+//! Boshqa dasturga joylash uchun eng oddiy yo'l:
+//!
+//! ```
+//! let natija = uzlang::run_source("x = 2\nx * 21").unwrap();
+//! assert_eq!(natija, uzlang::Value::Number(42));
+//! ```
+//!
+//! Chiqish, kirish va o'zgaruvchilarni boshqarish uchun [`Interpreter`] bilan ishlang:
+//!
+//! ```
+//! use uzlang::{CapturedOutput, Interpreter, Value};
+//!
+//! let mut interpreter = Interpreter::new();
+//! let output = CapturedOutput::new();
+//! interpreter.set_output(output.clone());
+//! interpreter.set_input(std::io::Cursor::new("Ali\n"));
+//! interpreter.set_variable("salom", Value::from("Assalomu alaykum"));
+//!
+//! interpreter.run_source("ism = so'ra\nyoz salom + \", \" + ism").unwrap();
+//! assert_eq!(output.contents(), "Assalomu alaykum, Ali\n");
+//! assert_eq!(interpreter.get_variable("ism"), Some(Value::from("Ali")));
+//! ```
+
+mod builtins;
+pub mod check;
+mod compiler;
+pub mod diagnostic;
+mod files;
+pub mod interpreter;
+pub mod lexer;
+pub mod limits;
+mod math;
+pub mod module;
+pub mod native;
+mod net;
+pub mod parser;
+pub mod repl;
+mod stdio;
+mod strings;
+mod vm;
+
+pub use diagnostic::{Diagnostic, Severity, Span};
+pub use interpreter::{Engine, Interpreter, RuntimeError, RuntimeErrorKind, Value};
+pub use limits::Limits;
+pub use native::{NativeArgs, NativeFn};
+pub use net::{NetworkAccess, NetworkPolicy};
+pub use stdio::CapturedOutput;
+
+use interpreter::ControlFlow;
+use lexer::Lexer;
+use parser::{Parser, StmtKind};
+use std::fmt;
+
+/// Dasturni bajarishdagi xato.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Lexer yoki parser xatolari; dastur umuman bajarilmagan
+    Syntax(Vec<Diagnostic>),
+    /// Bajarish paytidagi xato
+    Runtime(RuntimeError),
+}
+
+impl Error {
+    /// Xatoni manba qatorlari bilan chiqaradi.
+    pub fn render(&self, source: &str, filename: &str) -> String {
+        match self {
+            Error::Syntax(diagnostics) => diagnostics
+                .iter()
+                .map(|d| d.render(source, filename))
+                .collect(),
+            Error::Runtime(err) => err.render(source, filename),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax(diagnostics) => {
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", diagnostic)?;
+                }
+                Ok(())
+            }
+            Error::Runtime(err) => write!(f, "{}", err.to_diagnostic()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<RuntimeError> for Error {
+    fn from(err: RuntimeError) -> Self {
+        Error::Runtime(err)
+    }
+}
+
+/// Dasturni yangi interpreterda bajaradi va [`Interpreter::run_source`] natijasini qaytaradi.
+pub fn run_source(source: &str) -> Result<Value, Error> {
+    Interpreter::new().run_source(source)
+}
+
+impl Interpreter {
+    /// Manba matnini tahlil qiladi va shu interpreterda bajaradi.
+    ///
+    /// Natija — yuqori darajadagi `qaytar` qiymati yoki oxirgi qator ifoda bo'lsa, uning
+    /// qiymati. Ikkalasi ham bo'lmasa, funksiyalardagi kabi `0` qaytadi.
+    /// Ogohlantirishlar [`Interpreter::take_diagnostics`] orqali olinadi.
+    pub fn run_source(&mut self, source: &str) -> Result<Value, Error> {
+        Ok(self.eval_source(source)?.unwrap_or(Value::Number(0)))
+    }
+
+    /// [`Interpreter::run_source`] kabi, lekin natija bo'lmasa `None` qaytaradi.
+    pub fn eval_source(&mut self, source: &str) -> Result<Option<Value>, Error> {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize();
+        let mut parser = Parser::new(tokens);
+        let mut stmts = parser.parse();
+
+        let mut diagnostics = lexer.take_diagnostics();
+        diagnostics.extend(parser.take_diagnostics());
+        if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+            return Err(Error::Syntax(diagnostics));
+        }
+        self.diagnostics.extend(diagnostics);
+
+        // A trailing expression becomes a top-level return so its value is the result
+        if let Some(stmt) = stmts.last_mut()
+            && let StmtKind::Expr(expr) = &stmt.kind
+        {
+            stmt.kind = StmtKind::Return(expr.clone());
+        }
+        Ok(match self.execute(&stmts)? {
+            ControlFlow::Return(val) => Some(val),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_support::both_engines;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_run_source_result() {
+        assert_eq!(run_source("2 + 3"), Ok(Value::Number(5)));
+        assert_eq!(run_source("qaytar \"a\"\nyoz 1"), Ok(Value::from("a")));
+        assert_eq!(run_source("x = 1"), Ok(Value::Number(0)));
+
+        let Err(Error::Syntax(diagnostics)) = run_source("x = )") else {
+            panic!("sintaksis xatosi kutilgan");
+        };
+        assert_eq!(diagnostics[0].code, "P001");
+        let err = run_source("yoz yoq").unwrap_err();
+        assert!(matches!(&err, Error::Runtime(e) if e.kind.code() == "R001"));
+        assert_eq!(
+            err.to_string(),
+            "xato[R001] 1:5: O'zgaruvchi topilmadi: yoq"
+        );
+    }
+
+    #[test]
+    fn test_output_and_input_sinks() {
+        for mut interpreter in both_engines(|_| {}) {
+            let output = CapturedOutput::new();
+            interpreter.set_output(output.clone());
+            interpreter.set_input(Cursor::new("3\n  4  \n"));
+            interpreter
+                .run_source("a = son(so'ra)\nb = son(so'ra)\nyoz a + b\nyoz so'ra")
+                .unwrap();
+            // Exhausted input reads as an empty line
+            assert_eq!(output.contents(), "7\n\n");
+        }
+    }
+
+    #[test]
+    fn test_host_reads_and_sets_globals() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("chegara", Value::from(10));
+        interpreter
+            .run_source("funksiya kichikmi(x) { qaytar x < chegara }\nnatija = kichikmi(4)")
+            .unwrap();
+        assert_eq!(interpreter.get_variable("natija"), Some(Value::Bool(true)));
+
+        interpreter.set_variable("chegara", Value::from(2));
+        assert_eq!(
+            interpreter.run_source("kichikmi(4)"),
+            Ok(Value::Bool(false))
+        );
+        assert_eq!(interpreter.get_variable("yoq"), None);
+    }
+}
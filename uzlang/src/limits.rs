@@ -0,0 +1,346 @@
+use crate::diagnostic::Span;
+use crate::interpreter::{Interpreter, RuntimeError, RuntimeErrorKind, Value, format_float};
+use std::time::{Duration, Instant};
+
+/// Funksiyalar chaqiruvining standart chuqurligi.
+pub const DEFAULT_CALL_DEPTH: usize = 500;
+
+// When less stack than this is left at a call, the call continues on a new stack segment
+const STACK_RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+// How many steps pass between reads of the clock
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// Dastur bajarilishi cheklovlari. `None` — cheklanmagan.
+///
+/// Cheklovdan oshilsa, dastur R010 xatosi bilan to'xtaydi. Hisob har bir
+/// `run_source` yoki `execute` chaqiruvida boshidan boshlanadi.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Bajariladigan qadamlar: har bir buyruq va tsiklning har bir aylanishi
+    pub max_steps: Option<u64>,
+    /// Funksiyalar ichma-ich chaqirilishi mumkin bo'lgan chuqurlik.
+    /// Cheksiz rekursiya xotirani to'ldirmasligi uchun bu cheklov doim bor. Stek esa
+    /// kerak bo'lganda o'zi kengayadi, shuning uchun interpreter oddiy oqimda ham ishlaydi.
+    pub max_call_depth: usize,
+    /// Bitta massiv yoki lug'atdagi elementlar, bitta matndagi baytlar soni.
+    /// Qadamlar cheklovi bilan birga dastur ushlab turadigan xotirani ham cheklaydi:
+    /// har bir yangi qiymat kamida bitta qadam oladi. Oraliq nusxalar hisoblanmaydi,
+    /// shuning uchun `s = s + "a"` kabi uzun tsikllar ham ishlayveradi.
+    pub max_value_size: Option<usize>,
+    /// Dastur ishlashi mumkin bo'lgan eng ko'p vaqt
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_steps: None,
+            max_call_depth: DEFAULT_CALL_DEPTH,
+            max_value_size: None,
+            timeout: None,
+        }
+    }
+}
+
+impl Limits {
+    /// Talabalar dasturlarini avtomatik tekshirish uchun cheklovlar.
+    pub fn strict() -> Self {
+        Limits {
+            max_steps: Some(10_000_000),
+            max_call_depth: DEFAULT_CALL_DEPTH,
+            max_value_size: Some(1_000_000),
+            timeout: Some(Duration::from_secs(10)),
+        }
+    }
+
+    /// Massiv, lug'at yoki matn ruxsat etilgan hajmdan oshmaganini tekshiradi.
+    pub(crate) fn check_size(&self, value: &Value, span: Span) -> Result<(), RuntimeError> {
+        let Some(max) = self.max_value_size else {
+            return Ok(());
+        };
+        let message = match value {
+            Value::String(s) if s.len() > max => format!("Matn {} baytdan uzun bo'lib ketdi", max),
+            Value::Array(arr) if arr.len() > max => {
+                format!("Massiv {} ta elementdan katta bo'lib ketdi", max)
+            }
+            Value::Dict(dict) if dict.len() > max => {
+                format!("Lug'at {} ta elementdan katta bo'lib ketdi", max)
+            }
+            _ => return Ok(()),
+        };
+        Err(limit_error(message, span))
+    }
+}
+
+/// Joriy bajarilishda sarflangan qadamlar, chuqurlik va vaqt.
+#[derive(Debug, Default)]
+pub(crate) struct Budget {
+    steps: u64,
+    // Limits are only looked at once `steps` reaches this value
+    next_check: u64,
+    depth: usize,
+    started: Option<Instant>,
+    pub(crate) network_requests: u64,
+    pub(crate) network_bytes: u64,
+}
+
+fn limit_error(message: String, span: Span) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::Limit(message), span)
+}
+
+impl Interpreter {
+    /// Keyingi bajarilishlar uchun cheklovlarni belgilaydi.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Eng tashqi bajarilish boshlanganda hisobni nolga qaytaradi.
+    /// Modul yuklash kabi ichki bajarilishlar shu hisobdan foydalanadi.
+    pub(crate) fn begin_run(&mut self) -> bool {
+        if self.budget.started.is_some() {
+            return false;
+        }
+        self.budget = Budget {
+            started: Some(Instant::now()),
+            ..Budget::default()
+        };
+        true
+    }
+
+    pub(crate) fn end_run(&mut self) {
+        self.budget = Budget::default();
+    }
+
+    /// Bitta qadamni hisoblaydi: buyruq yoki tsiklning navbatdagi aylanishi.
+    #[inline]
+    pub(crate) fn step(&mut self, span: Span) -> Result<(), RuntimeError> {
+        self.budget.steps += 1;
+        if self.budget.steps < self.budget.next_check {
+            return Ok(());
+        }
+        self.check_budget(span)
+    }
+
+    fn check_budget(&mut self, span: Span) -> Result<(), RuntimeError> {
+        if let Some(max) = self.limits.max_steps
+            && self.budget.steps > max
+        {
+            return Err(limit_error(
+                format!(
+                    "Dastur {} qadamdan ko'p bajarildi, cheksiz tsikl bo'lishi mumkin",
+                    max
+                ),
+                span,
+            ));
+        }
+        if let (Some(timeout), Some(started)) = (self.limits.timeout, self.budget.started)
+            && started.elapsed() > timeout
+        {
+            return Err(limit_error(
+                format!(
+                    "Dastur {} soniyadan ko'p ishladi, cheksiz tsikl bo'lishi mumkin",
+                    format_float(timeout.as_secs_f64())
+                ),
+                span,
+            ));
+        }
+        let mut next = self.budget.steps + CLOCK_INTERVAL;
+        if let Some(max) = self.limits.max_steps {
+            next = next.min(max + 1);
+        }
+        self.budget.next_check = next;
+        Ok(())
+    }
+
+    /// Foydalanuvchi funksiyasiga kirishdan oldin chaqiriladi.
+    pub(crate) fn enter_call(&mut self, span: Span) -> Result<(), RuntimeError> {
+        if self.budget.depth >= self.limits.max_call_depth {
+            return Err(limit_error(
+                format!(
+                    "Funksiyalar {} qavatdan chuqur chaqirildi, cheksiz rekursiya bo'lishi mumkin",
+                    self.limits.max_call_depth
+                ),
+                span,
+            ));
+        }
+        self.budget.depth += 1;
+        Ok(())
+    }
+
+    pub(crate) fn leave_call(&mut self) {
+        self.budget.depth -= 1;
+    }
+
+    /// Foydalanuvchi funksiyasi tanasini bajaradi; stek tugab qolsa, yangi bo'lak ajratiladi.
+    pub(crate) fn with_stack<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || f(self))
+    }
+
+    /// Yangi yaratilgan qiymatni hajmi tekshirilgandan keyin qaytaradi.
+    pub(crate) fn sized(&self, value: Value, span: Span) -> Result<Value, RuntimeError> {
+        self.limits.check_size(&value, span)?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_support::{both_engines, run_limited};
+
+    fn run(code: &str, limits: Limits) -> Result<(), RuntimeError> {
+        run_limited(code, limits).map(|_| ())
+    }
+
+    fn run_err(code: &str, limits: Limits) -> RuntimeError {
+        let err = run(code, limits).expect_err("cheklov xatosi kutilgan edi");
+        assert_eq!(err.kind.code(), "R010");
+        err
+    }
+
+    fn steps(max: u64) -> Limits {
+        Limits {
+            max_steps: Some(max),
+            ..Limits::default()
+        }
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let err = run_err("x = 1\ntakrorla 1 { }", steps(100));
+        assert_eq!(err.span.line, 2);
+
+        // Two statements, the loop itself, three iterations and three bodies
+        let code = "x = 0\nn = 0\ntakrorla x < 3 { x = x + 1 }";
+        assert!(run(code, steps(9)).is_ok());
+        run_err(code, steps(8));
+
+        // Declaration and loop, two iterations, two calls and the `qaytar` run by each call
+        let code = "funksiya f(a) { qaytar a }\nuchun i ichida [1, 2] { f(i) }";
+        assert!(run(code, steps(8)).is_ok());
+        run_err(code, steps(7));
+
+        // Limits cannot be caught, so a loop around a try block still stops
+        run_err(
+            "takrorla 1 {\n urinib ko'r { x = 1 } xato bo'lsa { }\n}",
+            steps(100),
+        );
+    }
+
+    #[test]
+    fn test_budget_resets_between_runs() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(steps(50));
+        let code = "i = 0\ntakrorla i < 10 { i = i + 1 }";
+        assert!(interpreter.run_source(code).is_ok());
+        assert!(interpreter.run_source(code).is_ok());
+    }
+
+    #[test]
+    fn test_call_depth_limit() {
+        let limits = Limits {
+            max_call_depth: 20,
+            ..Limits::default()
+        };
+        let code = "funksiya f(n) {\n  agar n == 0 { qaytar 0 }\n  qaytar f(n - 1) + 1\n}";
+        assert!(run(&format!("{}\nx = f(19)", code), limits).is_ok());
+        let err = run_err(&format!("{}\nx = f(100)", code), limits);
+        assert_eq!(err.span.line, 3);
+        assert_eq!(err.trace.len(), 20);
+        let rendered = err.render(code, "a.uz");
+        assert!(rendered.contains("yuqoridagi qator yana 16 marta takrorlanadi"));
+
+        // Depth is released after an error, so the interpreter stays usable
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(limits);
+        assert!(interpreter.run_source(&format!("{}\nf(50)", code)).is_err());
+        assert_eq!(interpreter.run_source("f(15)"), Ok(Value::Number(15)));
+    }
+
+    #[test]
+    fn test_default_depth_fits_small_stack() {
+        // Embedders call `run_source` on ordinary threads, which get 2 MB of stack
+        let code = "funksiya f(n) {\n  agar n == 0 { qaytar 0 }\n  qaytar f(n - 1) + 1\n}";
+        std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(move || {
+                for mut interpreter in both_engines(|_| {}) {
+                    let depth = DEFAULT_CALL_DEPTH as i64 - 1;
+                    let result = interpreter.run_source(&format!("{}\nf({})", code, depth));
+                    assert_eq!(result, Ok(Value::Number(depth)));
+                    let err = interpreter
+                        .run_source(&format!("{}\nf(100000)", code))
+                        .expect_err("cheklov xatosi kutilgan edi");
+                    assert!(err.to_string().contains("R010"), "{}", err);
+
+                    // A higher limit is reachable too: the stack grows on the heap
+                    interpreter.set_limits(Limits {
+                        max_call_depth: 50_000,
+                        ..Limits::default()
+                    });
+                    let result = interpreter.run_source(&format!("{}\nf(20000)", code));
+                    assert_eq!(result, Ok(Value::Number(20_000)));
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_value_size_limit() {
+        let limits = Limits {
+            max_value_size: Some(100),
+            ..Limits::default()
+        };
+        let err = run_err("s = \"ab\"\ntakrorla 1 { s = s + s }", limits);
+        assert!(err.kind.to_string().contains("Matn 100 baytdan"));
+        let err = run_err("a = []\ntakrorla 1 { a = qosh(a, 1) }", limits);
+        assert!(err.kind.to_string().contains("Massiv 100 ta"));
+        let err = run_err("d = {}\ni = 0\ntakrorla 1 { d[i] = i\n i = i + 1 }", limits);
+        assert!(err.kind.to_string().contains("Lug'at 100 ta"));
+        assert_eq!(err.span.line, 3);
+        assert!(
+            run(
+                "a = []\nuchun i ichida [1, 2, 3] { a = qosh(a, i) }",
+                limits
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_long_append_loops_pass_strict_limits() {
+        let code = "s = \"\"
+            a = []
+            i = 0
+            takrorla i < 20000 {
+                s = s + \"a\"
+                agar i < 5000 { a = qosh(a, i) }
+                i = i + 1
+            }
+            n = uzunlik(a)";
+        let interpreter = run_limited(code, Limits::strict()).unwrap();
+        assert_eq!(
+            interpreter.get_variable("s").unwrap().to_string().len(),
+            20000
+        );
+        assert_eq!(interpreter.get_variable("n"), Some(Value::Number(5000)));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(20)),
+            ..Limits::default()
+        };
+        let err = run_err("takrorla 1 { }", limits);
+        assert!(err.kind.to_string().contains("soniyadan ko'p ishladi"));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::interpreter::{INTEGER_OVERFLOW, Interpreter, RuntimeError, Value};
+use crate::native::NativeArgs;
+use crate::parser::BinOp;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+/// Butun yoki haqiqiy son bo'lishi kerak bo'lgan argument.
+fn number_arg<'a>(args: &'a NativeArgs, index: usize) -> Result<&'a Value, RuntimeError> {
+    match args.get(index) {
+        value @ (Value::Number(_) | Value::Float(_)) => Ok(value),
+        other => Err(RuntimeError::type_mismatch(
+            format!(
+                "'{}' funksiyasining {}-parametri son bo'lishi kerak, {} berildi",
+                args.name,
+                index + 1,
+                other.type_name()
+            ),
+            args.span(index),
+        )),
+    }
+}
+
+fn as_f64(value: &Value) -> f64 {
+    match value {
+        Value::Number(n) => *n as f64,
+        Value::Float(n) => *n,
+        _ => unreachable!("checked by number_arg"),
+    }
+}
+
+fn mutlaq(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    match number_arg(args, 0)? {
+        Value::Number(n) => n
+            .checked_abs()
+            .map(Value::Number)
+            .ok_or_else(|| RuntimeError::arithmetic(INTEGER_OVERFLOW, args.span)),
+        value => Ok(Value::Float(as_f64(value).abs())),
+    }
+}
+
+/// `eng_kichik` va `eng_katta`: massivdagi sonlardan `better` tanlaganini qaytaradi.
+fn extreme(args: &NativeArgs, better: fn(f64, f64) -> bool) -> NativeResult {
+    let elements = args.array(0)?;
+    let mut best: Option<&Value> = None;
+    for element in elements.iter() {
+        if !matches!(element, Value::Number(_) | Value::Float(_)) {
+            return Err(RuntimeError::type_mismatch(
+                format!(
+                    "'{}' funksiyasi faqat sonlar massivi bilan ishlaydi, massivda {} bor",
+                    args.name,
+                    element.type_name()
+                ),
+                args.span(0),
+            ));
+        }
+        if best.is_none_or(|best| better(as_f64(element), as_f64(best))) {
+            best = Some(element);
+        }
+    }
+    best.cloned().ok_or_else(|| {
+        RuntimeError::type_mismatch(
+            format!("'{}' funksiyasiga bo'sh massiv berildi", args.name),
+            args.span(0),
+        )
+    })
+}
+
+fn ildiz(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let n = as_f64(number_arg(args, 0)?);
+    if n < 0.0 {
+        return Err(RuntimeError::arithmetic(
+            "manfiy sonning kvadrat ildizi yo'q",
+            args.span(0),
+        ));
+    }
+    Ok(Value::Float(n.sqrt()))
+}
+
+fn daraja(interpreter: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let base = number_arg(args, 0)?.clone();
+    let exponent = number_arg(args, 1)?.clone();
+    interpreter.evaluate_binary(base, BinOp::Pow, exponent, args.span)
+}
+
+fn yaxlitla(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    match number_arg(args, 0)? {
+        Value::Float(n) => {
+            let rounded = n.round();
+            // i64::MAX as f64 rounds up to 2^63, which is already out of range
+            if rounded >= i64::MIN as f64 && rounded < i64::MAX as f64 {
+                Ok(Value::Number(rounded as i64))
+            } else {
+                Err(RuntimeError::arithmetic(INTEGER_OVERFLOW, args.span))
+            }
+        }
+        value => Ok(value.clone()),
+    }
+}
+
+/// Matematik funksiyalar.
+pub(crate) fn register(interpreter: &mut Interpreter) {
+    interpreter.register_native("mutlaq", 1, "mutlaq(son): sonning absolyut qiymati", mutlaq);
+    interpreter.register_native(
+        "eng_kichik",
+        1,
+        "eng_kichik(massiv): sonlar massivining eng kichik elementi",
+        |_, args| extreme(args, |a, b| a < b),
+    );
+    interpreter.register_native(
+        "eng_katta",
+        1,
+        "eng_katta(massiv): sonlar massivining eng katta elementi",
+        |_, args| extreme(args, |a, b| a > b),
+    );
+    interpreter.register_native(
+        "ildiz",
+        1,
+        "ildiz(son): kvadrat ildiz, haqiqiy son qaytaradi",
+        ildiz,
+    );
+    interpreter.register_native(
+        "daraja",
+        2,
+        "daraja(asos, ko'rsatkich): asosni darajaga ko'taradi, 'asos ** ko'rsatkich' bilan bir xil",
+        daraja,
+    );
+    interpreter.register_native(
+        "yaxlitla",
+        1,
+        "yaxlitla(son): eng yaqin butun songa yaxlitlaydi (0,5 noldan uzoqqa)",
+        yaxlitla,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+    use crate::interpreter::test_support::answer as run;
+
+    fn text(code: &str) -> String {
+        run(code).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_modulo_and_power() {
+        assert_eq!(
+            text("javob = [17 % 5, (0 - 7) % 3, 7.5 % 2]"),
+            "[2; -1; 1,5]"
+        );
+        assert_eq!(
+            text("javob = [2 ** 10, 2 ** 3 ** 2, 2 * 3 ** 2, 2 ** (0 - 1)]"),
+            "[1024; 512; 18; 0,5]"
+        );
+        assert_eq!(text("javob = 4.0 ** 0.5"), "2,0");
+
+        let err = run("javob = 5 % 0").unwrap_err();
+        assert_eq!(err.kind.code(), "R012");
+        assert_eq!(
+            err.kind.to_string(),
+            "Hisoblash xatosi: nolga bo'lish mumkin emas"
+        );
+        let err = run("javob = 10 ** 19").unwrap_err();
+        assert_eq!(err.kind.code(), "R012");
+        assert_eq!(
+            text(
+                "javob = [1 ** 5000000000, 0 ** 5000000000, 0 ** 0, (0 - 1) ** 5000000001, (0 - 1) ** 5000000000]"
+            ),
+            "[1; 0; 1; -1; 1]"
+        );
+        assert_eq!(
+            run("javob = 2 ** 5000000000").unwrap_err().kind.code(),
+            "R012"
+        );
+        let err = run("javob = 0 ** (0 - 1)").unwrap_err();
+        assert_eq!(
+            err.kind.to_string(),
+            "Hisoblash xatosi: nolga bo'lish mumkin emas"
+        );
+        assert!(run("javob = \"a\" % 2").is_err());
+    }
+
+    #[test]
+    fn test_checked_integer_arithmetic() {
+        let max = i64::MAX;
+        for code in ["javob = 1 / 0", "javob = 0 / 0", "x = 0\njavob = 7 / x"] {
+            let err = run(code).unwrap_err();
+            assert_eq!(
+                err.kind.to_string(),
+                "Hisoblash xatosi: nolga bo'lish mumkin emas"
+            );
+        }
+        for code in [
+            format!("javob = {max} + 1"),
+            format!("javob = 0 - {max} - 2"),
+            format!("javob = {max} * 2"),
+            format!("javob = (0 - {max} - 1) / (0 - 1)"),
+        ] {
+            let err = run(&code).unwrap_err();
+            assert_eq!(err.kind.code(), "R012", "{}", code);
+            assert_eq!(
+                err.kind.to_string(),
+                "Hisoblash xatosi: natija butun son chegarasidan chiqib ketdi"
+            );
+        }
+        assert_eq!(
+            text(&format!(
+                "javob = [{max} - 1 + 1, (0 - {max} - 1) % (0 - 1), 7 / 2]"
+            )),
+            format!("[{max}; 0; 3]")
+        );
+        // Float division keeps IEEE semantics
+        assert_eq!(text("javob = 1.0 / 0 > 1"), "rost");
+        assert_eq!(
+            text("urinib ko'r { javob = 1 / 0 } xato bo'lsa e { javob = e[\"turi\"] }"),
+            "R012"
+        );
+    }
+
+    #[test]
+    fn test_oversized_literal_is_syntax_error() {
+        let mut interpreter = Interpreter::new();
+        match interpreter.run_source("javob = 99999999999999999999") {
+            Err(crate::Error::Syntax(diagnostics)) => assert_eq!(diagnostics[0].code, "L004"),
+            other => panic!("L004 kutilgan edi: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_school_exercises() {
+        let code = "funksiya ekub(a, b) {
+                takrorla b != 0 {
+                    q = a % b
+                    a = b
+                    b = q
+                }
+                qaytar a
+            }
+            funksiya tubmi(n) {
+                agar n < 2 { qaytar 0 }
+                i = 2
+                takrorla i * i <= n {
+                    agar n % i == 0 { qaytar 0 }
+                    i = i + 1
+                }
+                qaytar 1
+            }
+            tublar = []
+            uchun n ichida [1, 2, 3, 4, 5, 9, 11, 25, 29] {
+                agar tubmi(n) { tublar = qosh(tublar, n) }
+            }
+            javob = [ekub(84, 36), tublar]";
+        assert_eq!(text(code), "[12; [2; 3; 5; 11; 29]]");
+    }
+
+    #[test]
+    fn test_math_builtins() {
+        assert_eq!(
+            text("javob = [mutlaq(0 - 4), mutlaq(0 - 2.5), mutlaq(3)]"),
+            "[4; 2,5; 3]"
+        );
+        assert_eq!(
+            text("javob = [eng_kichik([3, 1.5, 2]), eng_katta([3, 7, 7, 2])]"),
+            "[1,5; 7]"
+        );
+        assert_eq!(text("javob = [ildiz(16), ildiz(2.25)]"), "[4,0; 1,5]");
+        assert_eq!(
+            text("javob = [daraja(3, 4), daraja(2, 0.5) > 1.41]"),
+            "[81; rost]"
+        );
+        assert_eq!(
+            text("javob = [yaxlitla(2.5), yaxlitla(0 - 2.5), yaxlitla(2.4), yaxlitla(7)]"),
+            "[3; -3; 2; 7]"
+        );
+
+        assert_eq!(run("javob = ildiz(0 - 1)").unwrap_err().kind.code(), "R012");
+        assert_eq!(
+            run("javob = eng_kichik([])").unwrap_err().kind.code(),
+            "R003"
+        );
+        assert_eq!(
+            run("javob = eng_katta([1, \"ikki\"])")
+                .unwrap_err()
+                .kind
+                .code(),
+            "R003"
+        );
+        assert_eq!(
+            run("javob = yaxlitla(10.0 ** 30)").unwrap_err().kind.code(),
+            "R012"
+        );
+        let err = run("javob = mutlaq(\"5\")").unwrap_err();
+        assert_eq!(
+            err.kind.to_string(),
+            "'mutlaq' funksiyasining 1-parametri son bo'lishi kerak, matn berildi"
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use crate::diagnostic::{Severity, Span};
+use crate::interpreter::{DictKey, Interpreter, RuntimeError, RuntimeErrorKind, Value};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Bitta `.uz` fayl va uning global o'zgaruvchilari.
+///
+/// Modul funksiyalari qayerdan chaqirilmasin, o'z modulining global doirasida bajariladi.
+pub struct Module {
+    /// Xato xabarlarida ko'rsatiladigan nom (ulash'da yozilgan yo'l)
+    pub name: String,
+    path: Option<PathBuf>,
+    // None for the main program: its source is owned by the host
+    source: Option<Rc<str>>,
+    // Globals of an inactive module; the active module's globals live in the interpreter
+    globals: RefCell<HashMap<Rc<str>, Value>>,
+}
+
+impl Module {
+    fn main(path: Option<PathBuf>) -> Rc<Module> {
+        Rc::new(Module {
+            name: String::new(),
+            path,
+            source: None,
+            globals: RefCell::default(),
+        })
+    }
+
+    /// Xato matnini chiqarish uchun modul manbasi va nomi.
+    /// Asosiy dastur uchun chaqiruvchi bergan qiymatlar ishlatiladi.
+    pub(crate) fn source_or<'a>(
+        &'a self,
+        source: &'a str,
+        filename: &'a str,
+    ) -> (&'a str, &'a str) {
+        match &self.source {
+            Some(own) => (own, &self.name),
+            None => (source, filename),
+        }
+    }
+}
+
+// The same file loaded by two interpreters is the same module
+impl PartialEq for Module {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.name == other.name
+    }
+}
+
+// Globals hold functions that point back to their module, so only the name is printed
+impl std::fmt::Debug for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Module").field("name", &self.name).finish()
+    }
+}
+
+/// Yuklangan modullar keshi va hozir bajarilayotgan modullar zanjiri.
+pub(crate) struct Modules {
+    pub(crate) current: Rc<Module>,
+    cache: HashMap<PathBuf, Rc<Module>>,
+    // Files whose top level is still running, for cycle detection
+    loading: Vec<PathBuf>,
+}
+
+impl Modules {
+    pub(crate) fn new() -> Self {
+        Modules {
+            current: Module::main(None),
+            cache: HashMap::new(),
+            loading: Vec::new(),
+        }
+    }
+}
+
+fn module_error(message: String, span: Span) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::Module(message), span)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+impl Interpreter {
+    /// Asosiy dastur faylini belgilaydi: `ulash` yo'llari shu fayl joylashgan papkaga
+    /// nisbatan qidiriladi. `set_file_root` chaqirilmagan bo'lsa, shu papka ildiz ham
+    /// bo'ladi. Hech qanday kod bajarilishidan oldin chaqirilishi kerak.
+    pub fn set_main_path(&mut self, path: &Path) {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(dir) = canonical.parent() {
+            self.default_file_root(dir);
+        }
+        self.modules.current = Module::main(Some(canonical.clone()));
+        self.modules.loading = vec![canonical];
+    }
+
+    /// `module` ni faol qiladi va avvalgi faol modulni qaytaradi.
+    fn enter_module(&mut self, module: &Rc<Module>) -> Rc<Module> {
+        let active = std::mem::take(self.globals_mut());
+        *self.modules.current.globals.borrow_mut() = active;
+        *self.globals_mut() = std::mem::take(&mut *module.globals.borrow_mut());
+        std::mem::replace(&mut self.modules.current, Rc::clone(module))
+    }
+
+    /// `f` ni `module` ning global doirasida bajaradi. Modul ichidagi xato `span`
+    /// (chaqiruv joyi) ga ko'chiriladi, asl joyi esa `inner` da saqlanadi.
+    pub(crate) fn run_in_module<T>(
+        &mut self,
+        module: &Rc<Module>,
+        span: Span,
+        f: impl FnOnce(&mut Self) -> Result<T, RuntimeError>,
+    ) -> Result<T, RuntimeError> {
+        if Rc::ptr_eq(module, &self.modules.current) {
+            return f(self);
+        }
+        let previous = self.enter_module(module);
+        let result = f(self);
+        self.enter_module(&previous);
+        result.map_err(|err| {
+            let mut outer = RuntimeError::new(err.kind.clone(), span);
+            outer.inner = Some(Box::new((Rc::clone(module), err)));
+            outer
+        })
+    }
+
+    /// `ulash "fayl"` ni bajaradi: modulni (bir marta) yuklaydi va uning funksiyalarini
+    /// global doiraga yoki `alias` nomli lug'atga joylaydi.
+    pub(crate) fn import_module(
+        &mut self,
+        path: &str,
+        alias: Option<&str>,
+        span: Span,
+    ) -> Result<(), RuntimeError> {
+        // Paths are relative to the importing file; without one, to the sandbox root
+        let base = self
+            .modules
+            .current
+            .path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""));
+        let file = self
+            .sandbox
+            .resolve_in(base, path)
+            .map_err(|message| module_error(message, span))?;
+        let canonical = fs::canonicalize(&file).map_err(|e| {
+            module_error(
+                format!("'{}' modulini ochib bo'lmadi: {}", file.display(), e),
+                span,
+            )
+        })?;
+
+        if let Some(start) = self.modules.loading.iter().position(|p| *p == canonical) {
+            let cycle: Vec<String> = self.modules.loading[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|p| file_name(p))
+                .collect();
+            return Err(module_error(
+                format!(
+                    "Modullar bir-birini aylana bo'ylab ulaydi: {}",
+                    cycle.join(" -> ")
+                ),
+                span,
+            ));
+        }
+
+        let module = match self.modules.cache.get(&canonical) {
+            Some(module) => Rc::clone(module),
+            None => self.load_module(path, canonical, span)?,
+        };
+
+        let exports: Vec<(Rc<str>, Value)> = module
+            .globals
+            .borrow()
+            .iter()
+            // Functions the module itself imported are not re-exported
+            .filter(|(_, val)| matches!(val, Value::Function(func) if Rc::ptr_eq(&func.module, &module)))
+            .map(|(name, val)| (name.clone(), val.clone()))
+            .collect();
+        match alias {
+            Some(alias) => {
+                let dict = exports
+                    .into_iter()
+                    .map(|(name, val)| (DictKey::String(name), val))
+                    .collect();
+                self.globals_mut()
+                    .insert(Rc::from(alias), Value::Dict(Rc::new(dict)));
+            }
+            None => self.globals_mut().extend(exports),
+        }
+        Ok(())
+    }
+
+    fn load_module(
+        &mut self,
+        name: &str,
+        canonical: PathBuf,
+        span: Span,
+    ) -> Result<Rc<Module>, RuntimeError> {
+        let source = fs::read_to_string(&canonical).map_err(|e| {
+            module_error(format!("'{}' modulini o'qib bo'lmadi: {}", name, e), span)
+        })?;
+
+        let mut lexer = Lexer::new(&source);
+        let tokens = lexer.tokenize();
+        let mut parser = Parser::new(tokens);
+        let stmts = parser.parse();
+        let mut diagnostics = lexer.take_diagnostics();
+        diagnostics.extend(parser.take_diagnostics());
+        let mut errors = diagnostics.iter().filter(|d| d.severity == Severity::Error);
+        if let Some(first) = errors.next() {
+            let rest = errors.count();
+            let more = if rest > 0 {
+                format!(" (va yana {} ta xato)", rest)
+            } else {
+                String::new()
+            };
+            return Err(module_error(
+                format!("'{}' modulida xato: {}{}", name, first, more),
+                span,
+            ));
+        }
+
+        let module = Rc::new(Module {
+            name: name.to_string(),
+            path: Some(canonical.clone()),
+            source: Some(Rc::from(source)),
+            globals: RefCell::default(),
+        });
+        self.modules.loading.push(canonical.clone());
+        let result = self.run_in_module(&module, span, |this| this.execute(&stmts));
+        self.modules.loading.pop();
+        result?;
+
+        self.modules.cache.insert(canonical, Rc::clone(&module));
+        Ok(module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_support::{run_configured, temp_project};
+
+    const MAIN: &str = "asosiy.uz";
+
+    /// `asosiy.uz` ni ikkala dvigatelda bajaradi va natijalar bir xilligini tekshiradi.
+    fn run(files: &[(&str, &str)]) -> Result<Interpreter, RuntimeError> {
+        let dir = temp_project(files);
+        let main = dir.join(MAIN);
+        let source = fs::read_to_string(&main).unwrap();
+        let result = run_configured(&source, |interpreter| interpreter.set_main_path(&main));
+        fs::remove_dir_all(dir).unwrap();
+        result
+    }
+
+    const LIB: &str = "\
+birlik = 1
+funksiya kopaytir(a, b) {
+    qaytar a * b * birlik
+}
+funksiya ikki_baravar(x) {
+    qaytar kopaytir(x, 2)
+}
+";
+
+    #[test]
+    fn test_import_into_globals() {
+        let interpreter = run(&[
+            (MAIN, "ulash \"lib.uz\"\nnatija = ikki_baravar(21)"),
+            ("lib.uz", LIB),
+        ])
+        .unwrap();
+        assert_eq!(interpreter.get_variable("natija"), Some(Value::Number(42)));
+        // Only functions are exported; they still see their own module's globals
+        assert_eq!(interpreter.get_variable("birlik"), None);
+    }
+
+    #[test]
+    fn test_import_under_namespace() {
+        let interpreter = run(&[
+            (
+                MAIN,
+                "ulash \"lib.uz\" sifatida lib\nnatija = lib.ikki_baravar(5) + lib[\"kopaytir\"](2, 3)",
+            ),
+            ("lib.uz", LIB),
+        ])
+        .unwrap();
+        assert_eq!(interpreter.get_variable("natija"), Some(Value::Number(16)));
+        assert_eq!(interpreter.get_variable("kopaytir"), None);
+    }
+
+    #[test]
+    fn test_paths_are_relative_to_importing_file() {
+        let interpreter = run(&[
+            (MAIN, "ulash \"kutubxona/a.uz\"\nnatija = a()"),
+            (
+                "kutubxona/a.uz",
+                "ulash \"b.uz\"\nfunksiya a() { qaytar b() + 1 }",
+            ),
+            ("kutubxona/b.uz", "funksiya b() { qaytar 41 }"),
+        ])
+        .unwrap();
+        assert_eq!(interpreter.get_variable("natija"), Some(Value::Number(42)));
+        assert_eq!(interpreter.get_variable("b"), None);
+    }
+
+    #[test]
+    fn test_module_loads_once() {
+        let interpreter = run(&[
+            (
+                MAIN,
+                "ulash \"lib.uz\" sifatida a\nulash \"./lib.uz\" sifatida b\nbir_xil = a.kopaytir == b.kopaytir",
+            ),
+            ("lib.uz", LIB),
+        ])
+        .unwrap();
+        assert_eq!(interpreter.get_variable("bir_xil"), Some(Value::Bool(true)));
+    }
+
+    #[test]
+    fn test_import_cycle_is_error() {
+        let err = run(&[
+            (MAIN, "ulash \"a.uz\""),
+            ("a.uz", "ulash \"b.uz\""),
+            ("b.uz", "ulash \"a.uz\""),
+        ])
+        .err()
+        .expect("xato kutilgan edi");
+        assert_eq!(
+            err.kind,
+            RuntimeErrorKind::Module(
+                "Modullar bir-birini aylana bo'ylab ulaydi: a.uz -> b.uz -> a.uz".to_string()
+            )
+        );
+
+        let err = run(&[(MAIN, "ulash \"asosiy.uz\"")])
+            .err()
+            .expect("xato kutilgan edi");
+        assert!(err.kind.to_string().ends_with("asosiy.uz -> asosiy.uz"));
+    }
+
+    #[test]
+    fn test_imports_stay_inside_root() {
+        let dir = temp_project(&[
+            ("sir.uz", "funksiya sir() { qaytar 1 }"),
+            ("ildiz/asosiy.uz", ""),
+            ("ildiz/ichki/lib.uz", "funksiya lib() { qaytar 2 }"),
+        ]);
+        let root = dir.join("ildiz");
+        let outside = dir.join("sir.uz").display().to_string();
+        for (path, allowed) in [
+            ("ichki/lib.uz", true),
+            ("ichki/../ichki/lib.uz", true),
+            ("../sir.uz", false),
+            ("ichki/../../sir.uz", false),
+            (outside.as_str(), false),
+        ] {
+            let code = format!("ulash \"{}\"", path);
+            let result = run_configured(&code, |interpreter| {
+                interpreter.set_file_root(&root);
+                interpreter.set_main_path(&root.join(MAIN));
+            });
+            match result {
+                Ok(_) => assert!(allowed, "{} ulanmasligi kerak edi", path),
+                Err(err) => {
+                    assert!(!allowed, "{}: {}", path, err.kind);
+                    assert_eq!(err.kind.code(), "R008");
+                    assert!(err.kind.to_string().contains("ildiz papkadan"));
+                }
+            }
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_default_root_is_main_program_folder() {
+        // The project lives in the temp dir, outside the working directory of the tests
+        let dir = temp_project(&[
+            (
+                MAIN,
+                "ulash \"lib.uz\"\nnatija = [lib(), fayl_oqi(\"malumot.txt\")]",
+            ),
+            ("lib.uz", "funksiya lib() { qaytar 1 }"),
+            ("malumot.txt", "ichida"),
+        ]);
+        assert!(!dir.starts_with(std::env::current_dir().unwrap()));
+        let main = dir.join(MAIN);
+        let source = fs::read_to_string(&main).unwrap();
+        let interpreter =
+            run_configured(&source, |interpreter| interpreter.set_main_path(&main)).unwrap();
+        assert_eq!(
+            interpreter.get_variable("natija").unwrap().to_string(),
+            "[1; ichida]"
+        );
+        run_configured("ulash \"../yoq.uz\"", |interpreter| {
+            interpreter.set_main_path(&main)
+        })
+        .err()
+        .expect("xato kutilgan edi");
+
+        // An explicit root is kept, whichever is set first
+        let err = run_configured("ulash \"lib.uz\"", |interpreter| {
+            interpreter.set_file_root(dir.join("ichki"));
+            interpreter.set_main_path(&main);
+        })
+        .err()
+        .expect("xato kutilgan edi");
+        assert_eq!(err.kind.code(), "R008");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_module_errors() {
+        let err = run(&[(MAIN, "ulash \"yoq.uz\"")])
+            .err()
+            .expect("xato kutilgan edi");
+        assert_eq!(err.kind.code(), "R008");
+
+        let err = run(&[(MAIN, "ulash \"lib.uz\""), ("lib.uz", "x = )")])
+            .err()
+            .expect("xato kutilgan edi");
+        assert!(err.kind.to_string().contains("P001"));
+
+        // Errors are shown in the file where they happened
+        let main = "ulash \"lib.uz\"\nfunksiya xato() { qaytar yoq }\nx = chaqir(xato)";
+        let err = run(&[
+            (MAIN, main),
+            ("lib.uz", "funksiya chaqir(f) {\n    qaytar f()\n}"),
+        ])
+        .err()
+        .expect("xato kutilgan edi");
+        let rendered = err.render(main, MAIN);
+        assert!(
+            rendered.starts_with("xato[R001]: O'zgaruvchi topilmadi: yoq\n --> asosiy.uz:2:26\n")
+        );
+        assert!(rendered.contains("'xato' funksiyasida, lib.uz:2:12 da chaqirilgan"));
+        assert!(rendered.contains("'chaqir' funksiyasida, asosiy.uz:3:5 da chaqirilgan"));
+
+        let err = run(&[(MAIN, "ulash \"lib.uz\""), ("lib.uz", "\n\nx = [1][5]")])
+            .err()
+            .expect("xato kutilgan edi");
+        let rendered = err.render("ulash \"lib.uz\"", MAIN);
+        assert!(rendered.contains(" --> lib.uz:3:"));
+        assert!(rendered.contains("'lib.uz' modulida, asosiy.uz:1:1 da ulangan"));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::diagnostic::Span;
+use crate::interpreter::{DictKey, Interpreter, RuntimeError, RuntimeErrorKind, Value};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+type Handler = dyn Fn(&mut Interpreter, &NativeArgs) -> Result<Value, RuntimeError>;
+
+/// Rust'da yozilgan funksiya. Dasturda oddiy funksiya qiymati kabi ishlatiladi,
+/// lekin foydalanuvchi shu nomda o'z funksiyasini e'lon qilsa, o'sha ustun turadi.
+pub struct NativeFn {
+    pub name: Rc<str>,
+    /// Kutilgan argumentlar soni; chaqirishdan oldin tekshiriladi
+    pub arity: usize,
+    /// Foydalanuvchiga ko'rsatiladigan qisqa tavsif
+    pub doc: Rc<str>,
+    handler: Box<Handler>,
+}
+
+impl NativeFn {
+    pub fn new(
+        name: &str,
+        arity: usize,
+        doc: &str,
+        handler: impl Fn(&mut Interpreter, &NativeArgs) -> Result<Value, RuntimeError> + 'static,
+    ) -> Self {
+        NativeFn {
+            name: Rc::from(name),
+            arity,
+            doc: Rc::from(doc),
+            handler: Box::new(handler),
+        }
+    }
+}
+
+// Natives are compared by identity, like user functions
+impl PartialEq for NativeFn {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl std::fmt::Debug for NativeFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("NativeFn")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
+/// Native funksiyaga berilgan argumentlar va ularning manbadagi joylari.
+pub struct NativeArgs<'a> {
+    pub name: &'a str,
+    pub values: Vec<Value>,
+    spans: &'a [Span],
+    /// Butun chaqiruv ifodasining joyi
+    pub span: Span,
+}
+
+impl NativeArgs<'_> {
+    /// `index`-argumentning joyi (xato xabarlari uchun).
+    pub fn span(&self, index: usize) -> Span {
+        self.spans.get(index).copied().unwrap_or(self.span)
+    }
+
+    pub fn get(&self, index: usize) -> &Value {
+        &self.values[index]
+    }
+
+    /// Matn bo'lishi kerak bo'lgan argument.
+    pub fn string(&self, index: usize) -> Result<&Rc<str>, RuntimeError> {
+        match &self.values[index] {
+            Value::String(s) => Ok(s),
+            other => Err(self.wrong_type(index, "matn", other)),
+        }
+    }
+
+    /// Butun son bo'lishi kerak bo'lgan argument.
+    pub fn number(&self, index: usize) -> Result<i64, RuntimeError> {
+        match &self.values[index] {
+            Value::Number(n) => Ok(*n),
+            other => Err(self.wrong_type(index, "butun son", other)),
+        }
+    }
+
+    /// Massiv bo'lishi kerak bo'lgan argument.
+    pub fn array(&self, index: usize) -> Result<&Rc<Vec<Value>>, RuntimeError> {
+        match &self.values[index] {
+            Value::Array(elements) => Ok(elements),
+            other => Err(self.wrong_type(index, "massiv", other)),
+        }
+    }
+
+    /// Lug'at bo'lishi kerak bo'lgan argument.
+    pub fn dict(&self, index: usize) -> Result<&Rc<BTreeMap<DictKey, Value>>, RuntimeError> {
+        match &self.values[index] {
+            Value::Dict(dict) => Ok(dict),
+            other => Err(self.wrong_type(index, "lug'at", other)),
+        }
+    }
+
+    fn wrong_type(&self, index: usize, expected: &str, given: &Value) -> RuntimeError {
+        RuntimeError::type_mismatch(
+            format!(
+                "'{}' funksiyasining {}-parametri {} bo'lishi kerak, {} berildi",
+                self.name,
+                index + 1,
+                expected,
+                given.type_name()
+            ),
+            self.span(index),
+        )
+    }
+}
+
+impl Interpreter {
+    /// Dasturga Rust funksiyasini qo'shadi. Shu nomdagi avvalgi native funksiya almashtiriladi.
+    ///
+    /// Noto'g'ri argument uchun jimgina 0 emas, xato qaytaring:
+    ///
+    /// ```
+    /// use uzlang::{Interpreter, RuntimeError, RuntimeErrorKind, Value};
+    ///
+    /// let mut interpreter = Interpreter::new();
+    /// interpreter.register_native("ikki_baravar", 1, "Sonni ikkiga ko'paytiradi", |_, args| {
+    ///     let n = args.number(0)?;
+    ///     n.checked_mul(2).map(Value::Number).ok_or_else(|| {
+    ///         RuntimeError::new(
+    ///             RuntimeErrorKind::Arithmetic("natija juda katta".to_string()),
+    ///             args.span,
+    ///         )
+    ///     })
+    /// });
+    ///
+    /// assert_eq!(interpreter.run_source("ikki_baravar(21)"), Ok(Value::Number(42)));
+    /// let err = interpreter.run_source("ikki_baravar(\"21\")").unwrap_err();
+    /// assert!(err.to_string().contains("R003"));
+    /// let err = interpreter.run_source("ikki_baravar(5000000000000000000)").unwrap_err();
+    /// assert!(err.to_string().contains("R012"));
+    /// ```
+    pub fn register_native(
+        &mut self,
+        name: &str,
+        arity: usize,
+        doc: &str,
+        handler: impl Fn(&mut Interpreter, &NativeArgs) -> Result<Value, RuntimeError> + 'static,
+    ) {
+        self.natives.insert(
+            Rc::from(name),
+            Rc::new(NativeFn::new(name, arity, doc, handler)),
+        );
+    }
+
+    /// Ro'yxatdan o'tgan native funksiya.
+    pub fn native(&self, name: &str) -> Option<&Rc<NativeFn>> {
+        self.natives.get(name)
+    }
+
+    /// Barcha native funksiyalar, nomi bo'yicha tartiblangan.
+    pub fn natives(&self) -> Vec<&Rc<NativeFn>> {
+        let mut natives: Vec<_> = self.natives.values().collect();
+        natives.sort_by(|a, b| a.name.cmp(&b.name));
+        natives
+    }
+
+    pub(crate) fn call_native(
+        &mut self,
+        native: &Rc<NativeFn>,
+        values: Vec<Value>,
+        arg_spans: &[Span],
+        span: Span,
+    ) -> Result<Value, RuntimeError> {
+        if values.len() != native.arity {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::ArgumentCount {
+                    name: native.name.to_string(),
+                    expected: native.arity,
+                    given: values.len(),
+                },
+                span,
+            ));
+        }
+        let args = NativeArgs {
+            name: &native.name,
+            values,
+            spans: arg_spans,
+            span,
+        };
+        let value = (native.handler)(self, &args)?;
+        self.sized(value, span)
+    }
+}
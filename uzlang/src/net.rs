@@ -0,0 +1,859 @@
+use crate::diagnostic::Span;
+use crate::interpreter::{DictKey, Interpreter, RuntimeError, Value};
+use crate::native::NativeArgs;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use std::io::{self, Read};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Manzil ochiq internetga tegishlimi: mahalliy, xususiy va maxsus tarmoqlar emas.
+pub(crate) fn is_safe_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ipv4) => {
+            let octets = ipv4.octets();
+            // Loopback 127.0.0.0/8
+            if octets[0] == 127 {
+                return false;
+            }
+            // Private 10.0.0.0/8
+            if octets[0] == 10 {
+                return false;
+            }
+            // Private 172.16.0.0/12
+            if octets[0] == 172 && (16..=31).contains(&octets[1]) {
+                return false;
+            }
+            // Private 192.168.0.0/16
+            if octets[0] == 192 && octets[1] == 168 {
+                return false;
+            }
+            // Link-local 169.254.0.0/16
+            if octets[0] == 169 && octets[1] == 254 {
+                return false;
+            }
+            // Current network 0.0.0.0/8
+            if octets[0] == 0 {
+                return false;
+            }
+            // CGNAT 100.64.0.0/10
+            if octets[0] == 100 && (64..=127).contains(&octets[1]) {
+                return false;
+            }
+            // Broadcast 255.255.255.255
+            if octets == [255, 255, 255, 255] {
+                return false;
+            }
+            true
+        }
+        IpAddr::V6(ipv6) => {
+            if ipv6.is_loopback() {
+                return false;
+            }
+            if ipv6.is_unspecified() {
+                return false;
+            }
+            let segments = ipv6.segments();
+            // Unique local fc00::/7
+            if (segments[0] & 0xfe00) == 0xfc00 {
+                return false;
+            }
+            // Link-local fe80::/10
+            if (segments[0] & 0xffc0) == 0xfe80 {
+                return false;
+            }
+            // IPv4-mapped ::ffff:0:0/96
+            if let Some(ipv4) = ipv6.to_ipv4() {
+                return is_safe_ip(IpAddr::V4(ipv4));
+            }
+            true
+        }
+    }
+}
+
+/// Host nomlarini IP manzillarga aylantiradi. Testlarda soxta DNS bilan almashtiriladi.
+pub(crate) trait Resolver {
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>>;
+
+    /// Shu manzilga ulanish mumkinmi.
+    fn is_public(&self, ip: IpAddr) -> bool {
+        is_safe_ip(ip)
+    }
+}
+
+/// Operatsion tizimning DNS'i.
+pub(crate) struct SystemResolver;
+
+impl Resolver for SystemResolver {
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        Ok((host, port).to_socket_addrs()?.collect())
+    }
+}
+
+const PRIVATE_NETWORK: &str = "Mahalliy yoki xususiy tarmoqqa ulanish taqiqlangan";
+
+/// Manzilni tekshiradi va faqat tekshirilgan IP manzillarga ulanadigan mijoz yaratadi.
+///
+/// Host nomi bir marta aniqlanadi va barcha javoblar tekshiriladi: bittasi ham mahalliy
+/// yoki xususiy tarmoqqa tegishli bo'lsa, so'rov rad etiladi. Mijoz shu manzillarga
+/// bog'lab qo'yiladi, shuning uchun keyingi DNS javoblari (DNS rebinding) ta'sir qilmaydi.
+/// Yo'naltirishlar (redirect) kuzatilmaydi va proksi ishlatilmaydi.
+pub(crate) fn create_safe_client(
+    url_str: &str,
+    resolver: &dyn Resolver,
+) -> Result<(Client, Url), &'static str> {
+    let url = Url::parse(url_str).map_err(|_| "Noto'g'ri manzil format")?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("Faqat HTTP/HTTPS ruxsat etilgan");
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let builder = Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .timeout(Duration::from_secs(10));
+    let host = url.host_str().ok_or("Noto'g'ri manzil format")?;
+    // IPv6 literals keep their brackets in URLs
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    let builder = match literal.parse::<IpAddr>() {
+        Ok(ip) if resolver.is_public(ip) => builder,
+        Ok(_) => return Err(PRIVATE_NETWORK),
+        Err(_) => {
+            // Defense in depth: these names always mean this machine
+            if host == "localhost" || host.ends_with(".localhost") {
+                return Err(PRIVATE_NETWORK);
+            }
+            let addrs = resolver.resolve(host, port).map_err(|_| "Host topilmadi")?;
+            if addrs.is_empty() {
+                return Err("Host topilmadi");
+            }
+            if !addrs.iter().all(|addr| resolver.is_public(addr.ip())) {
+                return Err(PRIVATE_NETWORK);
+            }
+            builder.resolve_to_addrs(host, &addrs)
+        }
+    };
+    let client = builder.build().map_err(|_| "Mijoz yaratishda xatolik")?;
+    Ok((client, url))
+}
+
+pub(crate) const MAX_RESPONSE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Skriptlar qaysi manzillarga so'rov yubora olishi.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum NetworkAccess {
+    /// Tarmoq funksiyalari hech qayerga ulanmaydi
+    Disabled,
+    /// Faqat shu hostlar va ularning subdomenlari
+    Hosts(Vec<String>),
+    /// Har qanday ochiq internet manzili
+    #[default]
+    Public,
+}
+
+/// Tarmoq funksiyalari siyosati.
+///
+/// Qaysi siyosat tanlanmasin, mahalliy va xususiy tarmoq manzillari
+/// (`create_safe_client`) baribir taqiqlangan. Qoidaga to'g'ri kelmagan so'rov
+/// yuborilmaydi: R005 ogohlantirishi chiqadi va bo'sh matn qaytadi.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NetworkPolicy {
+    pub access: NetworkAccess,
+    /// Bitta bajarilishda yuborish mumkin bo'lgan so'rovlar soni
+    pub max_requests: Option<u64>,
+    /// Bitta bajarilishda yuborilgan va qabul qilingan ma'lumotlar hajmi (bayt)
+    pub max_bytes: Option<u64>,
+}
+
+impl NetworkPolicy {
+    /// Tarmoq butunlay o'chirilgan siyosat.
+    pub fn disabled() -> Self {
+        NetworkPolicy {
+            access: NetworkAccess::Disabled,
+            ..NetworkPolicy::default()
+        }
+    }
+
+    /// Faqat berilgan hostlarga (va ularning subdomenlariga) ruxsat beradi.
+    pub fn hosts<S: AsRef<str>>(hosts: impl IntoIterator<Item = S>) -> Self {
+        NetworkPolicy {
+            access: NetworkAccess::Hosts(
+                hosts
+                    .into_iter()
+                    .map(|host| host.as_ref().trim().to_lowercase())
+                    .filter(|host| !host.is_empty())
+                    .collect(),
+            ),
+            ..NetworkPolicy::default()
+        }
+    }
+
+    /// Manzilga siyosat bo'yicha ruxsat bormi; bo'lmasa, sababini qaytaradi.
+    fn check_access(&self, url: &str) -> Result<(), String> {
+        match &self.access {
+            NetworkAccess::Public => Ok(()),
+            NetworkAccess::Disabled => Err("Tarmoqdan foydalanish o'chirilgan".to_string()),
+            NetworkAccess::Hosts(hosts) => {
+                let host = reqwest::Url::parse(url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_lowercase))
+                    .ok_or_else(|| "Noto'g'ri manzil format".to_string())?;
+                let allowed = hosts.iter().any(|allowed| {
+                    host == *allowed
+                        || host
+                            .strip_suffix(allowed.as_str())
+                            .is_some_and(|sub| sub.ends_with('.'))
+                });
+                if allowed {
+                    Ok(())
+                } else {
+                    Err(format!("'{}' hostiga ruxsat berilmagan", host))
+                }
+            }
+        }
+    }
+}
+
+impl Interpreter {
+    /// Tarmoq funksiyalari siyosatini belgilaydi (standart: har qanday ochiq manzil).
+    pub fn set_network_policy(&mut self, policy: NetworkPolicy) {
+        self.network = policy;
+    }
+
+    pub fn network_policy(&self) -> &NetworkPolicy {
+        &self.network
+    }
+
+    /// Siyosat va shu bajarilishdagi byudjet so'rovga ruxsat beradimi.
+    fn check_request(&self, url: &str, body_len: u64) -> Result<(), String> {
+        self.network.check_access(url)?;
+        if let Some(max) = self.network.max_requests
+            && self.budget.network_requests >= max
+        {
+            return Err(format!("So'rovlar soni cheklovi ({}) tugadi", max));
+        }
+        if let Some(max) = self.network.max_bytes
+            && self.budget.network_bytes + body_len > max
+        {
+            return Err(format!("Tarmoq hajmi cheklovi ({} bayt) tugadi", max));
+        }
+        Ok(())
+    }
+
+    /// HTTP so'rovini yuboradi. Xato bo'lsa, foydalanuvchiga ko'rsatiladigan sababni qaytaradi.
+    fn send(&mut self, request: HttpRequest) -> Result<HttpResponse, String> {
+        let url_str = request.url.as_str();
+        let body_len = request.body.as_ref().map_or(0, |body| body.len() as u64);
+        self.check_request(url_str, body_len)
+            .map_err(|msg| format!("Tarmoq siyosati: {}: {}", msg, url_str))?;
+        let (client, url) = create_safe_client(url_str, &*self.resolver)
+            .map_err(|msg| format!("Xavfsizlik qoidasi buzildi - {}: {}", msg, url_str))?;
+
+        self.budget.network_requests += 1;
+        self.budget.network_bytes += body_len;
+        // Client is pinned to the checked IP and does not follow redirects
+        let mut builder = client.request(request.method, url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let resp = builder
+            .send()
+            .map_err(|e| format!("Internet so'rovida xatolik: {}", e))?;
+
+        let status = resp.status().as_u16();
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for (name, value) in resp.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            // Repeated headers are joined the way HTTP allows
+            match headers.iter_mut().find(|(n, _)| n == name.as_str()) {
+                Some((_, existing)) => {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                }
+                None => headers.push((name.as_str().to_string(), value)),
+            }
+        }
+
+        // One byte past the remaining budget is enough to tell that it ran out
+        let remaining = self
+            .network
+            .max_bytes
+            .map(|max| max.saturating_sub(self.budget.network_bytes));
+        let cap = remaining.map_or(MAX_RESPONSE_SIZE, |left| {
+            MAX_RESPONSE_SIZE.min(left.saturating_add(1))
+        });
+        let mut body = String::new();
+        resp.take(cap)
+            .read_to_string(&mut body)
+            .map_err(|_| "Javobni o'qishda xatolik".to_string())?;
+        self.budget.network_bytes += body.len() as u64;
+        if let (Some(max), Some(left)) = (self.network.max_bytes, remaining)
+            && body.len() as u64 > left
+        {
+            return Err(format!(
+                "Tarmoq siyosati: Tarmoq hajmi cheklovi ({} bayt) tugadi: {}",
+                max, url_str
+            ));
+        }
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    /// `internet_ol` va `internet_yoz` uchun: xato bo'lsa ogohlantirish va bo'sh matn.
+    fn send_for_body(&mut self, request: HttpRequest, span: Span) -> Value {
+        match self.send(request) {
+            Ok(response) => Value::String(Rc::from(response.body)),
+            Err(msg) => {
+                self.warn("R005", msg, span);
+                Value::empty_string()
+            }
+        }
+    }
+}
+
+struct HttpRequest {
+    method: Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+}
+
+struct HttpResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl HttpResponse {
+    /// `so'rov` natijasi: `holat`, `sarlavhalar`, `tana` va `xato` kalitli lug'at.
+    fn into_value(self) -> Value {
+        let headers = self
+            .headers
+            .into_iter()
+            .map(|(name, value)| (DictKey::String(Rc::from(name)), Value::from(value)))
+            .collect();
+        response_value(
+            Value::Number(self.status as i64),
+            Value::Dict(Rc::new(headers)),
+            Value::from(self.body),
+            Value::empty_string(),
+        )
+    }
+}
+
+fn response_value(status: Value, headers: Value, body: Value, error: Value) -> Value {
+    let fields = [
+        ("holat", status),
+        ("sarlavhalar", headers),
+        ("tana", body),
+        ("xato", error),
+    ];
+    Value::Dict(Rc::new(
+        fields
+            .into_iter()
+            .map(|(key, value)| (DictKey::String(Rc::from(key)), value))
+            .collect(),
+    ))
+}
+
+fn so_rov(interpreter: &mut Interpreter, args: &NativeArgs) -> Result<Value, RuntimeError> {
+    let method = match args.string(0)?.to_uppercase().as_str() {
+        "GET" => Method::GET,
+        "POST" => Method::POST,
+        "PUT" => Method::PUT,
+        "DELETE" => Method::DELETE,
+        other => {
+            return Err(RuntimeError::type_mismatch(
+                format!(
+                    "So'rov usuli GET, POST, PUT yoki DELETE bo'lishi kerak, '{}' berildi",
+                    other
+                ),
+                args.span(0),
+            ));
+        }
+    };
+    let url = args.string(1)?.to_string();
+    let headers = args
+        .dict(2)?
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    let body = args.string(3)?;
+    let request = HttpRequest {
+        method,
+        url,
+        headers,
+        body: (!body.is_empty()).then(|| body.to_string()),
+    };
+    Ok(match interpreter.send(request) {
+        Ok(response) => response.into_value(),
+        Err(msg) => response_value(
+            Value::Number(0),
+            Value::Dict(Rc::default()),
+            Value::empty_string(),
+            Value::from(msg),
+        ),
+    })
+}
+
+/// `internet_ol`, `internet_yoz` va `so'rov`.
+pub(crate) fn register(interpreter: &mut Interpreter) {
+    interpreter.register_native(
+        "internet_ol",
+        1,
+        "internet_ol(manzil): HTTP GET so'rovi javobini matn sifatida qaytaradi",
+        |interpreter, args| {
+            let request = HttpRequest {
+                method: Method::GET,
+                url: args.get(0).to_string(),
+                headers: Vec::new(),
+                body: None,
+            };
+            Ok(interpreter.send_for_body(request, args.span))
+        },
+    );
+    interpreter.register_native(
+        "internet_yoz",
+        2,
+        "internet_yoz(manzil, json): JSON ma'lumotni HTTP POST bilan yuboradi",
+        |interpreter, args| {
+            let request = HttpRequest {
+                method: Method::POST,
+                url: args.get(0).to_string(),
+                headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+                body: Some(args.get(1).to_string()),
+            };
+            Ok(interpreter.send_for_body(request, args.span))
+        },
+    );
+    interpreter.register_native(
+        "so'rov",
+        4,
+        "so'rov(usul, manzil, sarlavhalar, tana): GET, POST, PUT yoki DELETE so'rovi; \
+         holat, sarlavhalar, tana va xato kalitli lug'at qaytaradi",
+        so_rov,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_support::run_configured;
+    use std::cell::Cell;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Runs on both engines, each asking `resolver` for its DNS, and returns
+    /// the value of `javob` and the warnings.
+    fn run_with_dns(
+        code: &str,
+        policy: &NetworkPolicy,
+        mut resolver: impl FnMut() -> Rc<dyn Resolver>,
+    ) -> (Value, Vec<String>) {
+        let mut interpreter = run_configured(code, |interpreter| {
+            interpreter.set_network_policy(policy.clone());
+            interpreter.resolver = resolver();
+        })
+        .unwrap();
+        let warnings = interpreter
+            .take_diagnostics()
+            .into_iter()
+            .map(|d| format!("{}: {}", d.code, d.message))
+            .collect();
+        (interpreter.get_variable("javob").unwrap(), warnings)
+    }
+
+    /// Runs on both engines with the system DNS.
+    fn run(code: &str, policy: &NetworkPolicy) -> (Value, Vec<String>) {
+        run_with_dns(code, policy, || Rc::new(SystemResolver))
+    }
+
+    /// Local HTTP server that records every request and sends the same response.
+    struct MockServer {
+        addr: SocketAddr,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockServer {
+        fn start(response: &'static str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let recorded = Arc::clone(&requests);
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let request = read_request(&stream);
+                    recorded.lock().unwrap().push(request);
+                    let _ = (&stream).write_all(response.as_bytes());
+                }
+            });
+            MockServer { addr, requests }
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        fn url(&self, path: &str) -> String {
+            format!("http://maktab.test:{}{}", self.addr.port(), path)
+        }
+    }
+
+    /// "METHOD /path x-header: value body" of one request; only `x-` headers are kept.
+    fn read_request(stream: &TcpStream) -> String {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut parts = request_line.split(' ');
+        let mut request = format!("{} {}", parts.next().unwrap(), parts.next().unwrap());
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                break;
+            }
+            let line = line.trim_end().to_lowercase();
+            if let Some(len) = line.strip_prefix("content-length:") {
+                content_length = len.trim().parse().unwrap();
+            } else if line.starts_with("x-") {
+                request.push(' ');
+                request.push_str(&line);
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        format!("{} {}", request, String::from_utf8_lossy(&body))
+            .trim_end()
+            .to_string()
+    }
+
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nsalom";
+
+    /// Answers DNS queries from a script. In this test world the mock server's
+    /// loopback address stands in for a public host.
+    struct StubDns {
+        answers: Vec<Vec<SocketAddr>>,
+        calls: Cell<usize>,
+        public: IpAddr,
+    }
+
+    impl StubDns {
+        fn new(server: &MockServer, answers: Vec<Vec<SocketAddr>>) -> Rc<Self> {
+            Rc::new(StubDns {
+                answers,
+                calls: Cell::new(0),
+                public: server.addr.ip(),
+            })
+        }
+    }
+
+    impl Resolver for StubDns {
+        fn resolve(&self, _host: &str, _port: u16) -> io::Result<Vec<SocketAddr>> {
+            let call = self.calls.get();
+            self.calls.set(call + 1);
+            Ok(self.answers[call.min(self.answers.len() - 1)].clone())
+        }
+
+        fn is_public(&self, ip: IpAddr) -> bool {
+            ip == self.public || is_safe_ip(ip)
+        }
+    }
+
+    fn private_addr(server: &MockServer) -> SocketAddr {
+        SocketAddr::new(IpAddr::from([10, 0, 0, 1]), server.addr.port())
+    }
+
+    #[test]
+    fn test_is_safe_ip_v4() {
+        assert!(!is_safe_ip("127.0.0.1".parse().unwrap()));
+        assert!(!is_safe_ip("10.0.0.1".parse().unwrap()));
+        assert!(!is_safe_ip("192.168.1.1".parse().unwrap()));
+        assert!(!is_safe_ip("172.16.0.1".parse().unwrap()));
+        assert!(!is_safe_ip("169.254.1.1".parse().unwrap()));
+        assert!(!is_safe_ip("0.0.0.0".parse().unwrap()));
+        assert!(is_safe_ip("8.8.8.8".parse().unwrap()));
+        assert!(is_safe_ip("1.1.1.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_is_safe_ip_v6() {
+        assert!(!is_safe_ip("::1".parse().unwrap()));
+        assert!(!is_safe_ip("::".parse().unwrap()));
+        assert!(!is_safe_ip("fc00::1".parse().unwrap()));
+        assert!(!is_safe_ip("fe80::1".parse().unwrap()));
+        assert!(!is_safe_ip("::ffff:127.0.0.1".parse().unwrap()));
+        assert!(is_safe_ip("2001:db8::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_local_addresses_are_rejected_without_dns() {
+        for url in [
+            "http://127.0.0.1:8080/",
+            "http://2130706433/",
+            "http://[::1]/",
+            "http://[::ffff:10.0.0.1]/",
+            "http://localhost/",
+            "http://ichki.localhost/",
+        ] {
+            assert_eq!(
+                create_safe_client(url, &SystemResolver).err(),
+                Some(PRIVATE_NETWORK),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(
+            create_safe_client("file:///etc/passwd", &SystemResolver).err(),
+            Some("Faqat HTTP/HTTPS ruxsat etilgan")
+        );
+    }
+
+    #[test]
+    fn test_requests_use_the_checked_address() {
+        let server = MockServer::start(OK);
+        let code = format!(
+            "javob = internet_ol(\"{}\")\nikkinchi = internet_yoz(\"{}\", \"[1, 2]\")",
+            server.url("/dars?n=1"),
+            server.url("/")
+        );
+        let mut stubs = Vec::new();
+        let (javob, warnings) = run_with_dns(&code, &NetworkPolicy::default(), || {
+            let dns = StubDns::new(&server, vec![vec![server.addr]]);
+            stubs.push(Rc::clone(&dns));
+            dns
+        });
+        assert_eq!(javob, Value::from("salom"));
+        assert_eq!(warnings, Vec::<String>::new());
+        assert_eq!(
+            stubs.iter().map(|dns| dns.calls.get()).collect::<Vec<_>>(),
+            [2, 2]
+        );
+        assert_eq!(
+            server.requests(),
+            [
+                "GET /dars?n=1",
+                "POST / [1, 2]",
+                "GET /dars?n=1",
+                "POST / [1, 2]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_resolved_address_is_checked() {
+        let server = MockServer::start(OK);
+        let code = format!("javob = internet_ol(\"{}\")", server.url("/"));
+        // A public answer first does not hide a private one behind it
+        let (javob, warnings) = run_with_dns(&code, &NetworkPolicy::default(), || {
+            StubDns::new(&server, vec![vec![server.addr, private_addr(&server)]])
+        });
+        assert_eq!(javob, Value::empty_string());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(PRIVATE_NETWORK));
+        assert_eq!(server.requests(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_dns_rebinding_is_blocked() {
+        // After the first, checked answer the name points at a private address.
+        // The client must connect to the checked address without asking again.
+        let server = MockServer::start(OK);
+        let code = format!("javob = internet_ol(\"{}\")", server.url("/"));
+        let mut stubs = Vec::new();
+        let (javob, warnings) = run_with_dns(&code, &NetworkPolicy::default(), || {
+            let dns = StubDns::new(
+                &server,
+                vec![vec![server.addr], vec![private_addr(&server)]],
+            );
+            stubs.push(Rc::clone(&dns));
+            dns
+        });
+        assert_eq!(javob, Value::from("salom"));
+        assert_eq!(warnings, Vec::<String>::new());
+        assert_eq!(
+            stubs.iter().map(|dns| dns.calls.get()).collect::<Vec<_>>(),
+            [1, 1]
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_redirects_are_not_followed() {
+        let server = MockServer::start(
+            "HTTP/1.1 302 Found\r\nLocation: http://10.0.0.1/\r\nContent-Length: 3\r\nConnection: close\r\n\r\nbor",
+        );
+        let code = format!("javob = internet_ol(\"{}\")", server.url("/"));
+        let (javob, _) = run_with_dns(&code, &NetworkPolicy::default(), || {
+            StubDns::new(&server, vec![vec![server.addr]])
+        });
+        assert_eq!(javob, Value::from("bor"));
+        assert_eq!(server.requests(), ["GET /", "GET /"]);
+    }
+
+    /// `so'rov` result fields as an array: status, `x-til` header, body and error.
+    const SOROV_FIELDS: &str =
+        "javob = [r[\"holat\"], r[\"sarlavhalar\"][\"x-til\"], r[\"tana\"], r[\"xato\"]]";
+
+    #[test]
+    fn test_sorov_methods_and_headers() {
+        let server = MockServer::start(
+            "HTTP/1.1 201 Created\r\nX-Til: uz\r\nX-Til: ru\r\nContent-Length: 2\r\nConnection: close\r\n\r\nha",
+        );
+        let code = format!(
+            "s = {{\"X-Kalit\": \"abc\", \"X-Son\": 5}}\n\
+             r = so'rov(\"put\", \"{0}\", s, \"[1]\")\n\
+             so'rov(\"DELETE\", \"{0}\", {{}}, \"\")\n\
+             so'rov(\"Get\", \"{0}\", {{}}, \"\")\n\
+             so‘rov(\"post\", \"{0}\", {{}}, \"x=1\")\n{1}",
+            server.url("/dars"),
+            SOROV_FIELDS
+        );
+        let (javob, warnings) = run_with_dns(&code, &NetworkPolicy::default(), || {
+            StubDns::new(&server, vec![vec![server.addr]])
+        });
+        assert_eq!(
+            javob,
+            Value::from(vec![
+                Value::Number(201),
+                Value::from("uz, ru"),
+                Value::from("ha"),
+                Value::empty_string(),
+            ])
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+        assert_eq!(
+            server.requests()[..4],
+            [
+                "PUT /dars x-kalit: abc x-son: 5 [1]",
+                "DELETE /dars",
+                "GET /dars",
+                "POST /dars x=1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sorov_errors() {
+        // Failures are reported in the result instead of a warning
+        let fields =
+            "javob = [r[\"holat\"], uzunlik(r[\"sarlavhalar\"]), r[\"tana\"], r[\"xato\"]]";
+        let code = format!(
+            "r = so'rov(\"GET\", \"http://127.0.0.1/\", {{}}, \"\")\n{}",
+            fields
+        );
+        let (javob, warnings) = run(&code, &NetworkPolicy::default());
+        assert_eq!(
+            javob,
+            Value::from(vec![
+                Value::Number(0),
+                Value::Number(0),
+                Value::empty_string(),
+                Value::from(format!(
+                    "Xavfsizlik qoidasi buzildi - {}: http://127.0.0.1/",
+                    PRIVATE_NETWORK
+                )),
+            ])
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+
+        let code = format!(
+            "r = so'rov(\"GET\", \"https://example.com\", {{}}, \"\")\n{}",
+            fields
+        );
+        let (javob, _) = run(&code, &NetworkPolicy::disabled());
+        let Value::Array(values) = javob else {
+            panic!("massiv kutilgan edi");
+        };
+        assert!(values[3].to_string().starts_with("Tarmoq siyosati"));
+
+        for (code, message) in [
+            (
+                "so'rov(\"PATCH\", \"https://example.com\", {}, \"\")",
+                "So'rov usuli GET, POST, PUT yoki DELETE bo'lishi kerak, 'PATCH' berildi",
+            ),
+            (
+                "so'rov(\"GET\", \"https://example.com\", [], \"\")",
+                "'so'rov' funksiyasining 3-parametri lug'at bo'lishi kerak, massiv berildi",
+            ),
+        ] {
+            let err = run_configured(code, |interpreter| {
+                interpreter.set_network_policy(NetworkPolicy::disabled())
+            })
+            .err()
+            .expect("xato kutilgan edi");
+            assert!(err.kind.to_string().contains(message), "{}", err.kind);
+        }
+    }
+
+    #[test]
+    fn test_disabled_network_sends_nothing() {
+        let (javob, warnings) = run(
+            "javob = internet_ol(\"https://example.com\")",
+            &NetworkPolicy::disabled(),
+        );
+        assert_eq!(javob, Value::empty_string());
+        assert_eq!(
+            warnings,
+            vec![
+                "R005: Tarmoq siyosati: Tarmoqdan foydalanish o'chirilgan: https://example.com"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_host_allow_list() {
+        let policy = NetworkPolicy::hosts(["Maktab.uz"]);
+        assert!(policy.check_access("https://maktab.uz/dars").is_ok());
+        assert!(policy.check_access("http://api.maktab.uz:8080/").is_ok());
+        assert!(policy.check_access("https://boshqamaktab.uz").is_err());
+        assert!(
+            policy
+                .check_access("https://maktab.uz.example.com")
+                .is_err()
+        );
+        assert!(policy.check_access("manzil emas").is_err());
+
+        let (_, warnings) = run(
+            "javob = internet_yoz(\"https://example.com\", \"{}\")",
+            &policy,
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "R005: Tarmoq siyosati: 'example.com' hostiga ruxsat berilmagan: https://example.com"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_request_budgets() {
+        let policy = NetworkPolicy {
+            max_requests: Some(0),
+            ..NetworkPolicy::default()
+        };
+        let (_, warnings) = run("javob = internet_ol(\"https://example.com\")", &policy);
+        assert!(warnings[0].contains("So'rovlar soni cheklovi (0) tugadi"));
+
+        // The request body alone is over the byte budget, so nothing is sent
+        let policy = NetworkPolicy {
+            max_bytes: Some(4),
+            ..NetworkPolicy::default()
+        };
+        let (_, warnings) = run(
+            "javob = internet_yoz(\"https://example.com\", \"[1, 2, 3]\")",
+            &policy,
+        );
+        assert!(warnings[0].contains("Tarmoq hajmi cheklovi (4 bayt) tugadi"));
+    }
+}
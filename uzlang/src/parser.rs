@@ -1,288 +1,575 @@
-use crate::lexer::Token;
+use crate::diagnostic::{Diagnostic, Span};
+use crate::lexer::{SpannedToken, Token};
+use std::rc::Rc;
 
 #[derive(Debug, Clone)]
-pub enum Expr {
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
     Number(i64),
+    Float(f64),
     StringLiteral(String),
+    Bool(bool),
     Identifier(String),
-    BinaryOp(Box<Expr>, String, Box<Expr>),
-    UnaryOp(String, Box<Expr>),
-    Call(String, Vec<Expr>),
+    BinaryOp(Box<Expr>, BinOp, Box<Expr>),
+    UnaryOp(UnOp, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    Function(Rc<FunctionDecl>), // nomsiz funksiya: funksiya(x) { ... }
     Input,
     Array(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
     Index(Box<Expr>, Box<Expr>),
 }
 
+/// Ikki operandli amal. Noma'lum amallar tahlil bosqichidayoq rad etiladi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Eq,
+    NotEq,
+    Gt,
+    Lt,
+    GtEq,
+    LtEq,
+    And,
+    Or,
+}
+
+impl BinOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Mod => "%",
+            BinOp::Pow => "**",
+            BinOp::Eq => "==",
+            BinOp::NotEq => "!=",
+            BinOp::Gt => ">",
+            BinOp::Lt => "<",
+            BinOp::GtEq => ">=",
+            BinOp::LtEq => "<=",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    Not,
+}
+
 #[derive(Debug, Clone)]
-pub enum Stmt {
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum StmtKind {
     Print(Expr),
-    If(Expr, Vec<Stmt>),
-    Loop(Expr, Vec<Stmt>),        // while
-    For(String, Expr, Vec<Stmt>), // for
+    If(Vec<(Expr, Vec<Stmt>)>, Option<Vec<Stmt>>), // agar / yoki agar branches, aks holda
+    Loop(Expr, Vec<Stmt>),                         // while
+    For(String, Expr, Vec<Stmt>),                  // for
     Assign(String, Expr),
     AssignIndex(String, Expr, Expr), // name, index, value
-    Function(String, Vec<String>, Vec<Stmt>),
+    Function(String, Rc<FunctionDecl>),
     Return(Expr),
+    Break,
+    Continue,
+    Import(String, Option<String>), // ulash "fayl.uz" [sifatida nom]
+    Try(Vec<Stmt>, Option<String>, Vec<Stmt>), // urinib ko'r { } xato bo'lsa [nom] { }
     Expr(Expr),
 }
 
+/// Funksiya parametrlari va tanasi. Nomli va nomsiz funksiyalar uchun umumiy,
+/// `Rc` orqali ijro vaqtidagi funksiya qiymatlari bilan bo'lishiladi.
+#[derive(Debug)]
+pub struct FunctionDecl {
+    pub params: Vec<Rc<str>>,
+    pub body: Vec<Stmt>,
+}
+
+impl Expr {
+    fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+}
+
+impl Stmt {
+    fn new(kind: StmtKind, span: Span) -> Self {
+        Stmt { kind, span }
+    }
+}
+
+type ParseResult<T> = Result<T, Diagnostic>;
+
 pub struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<SpannedToken>,
     pos: usize,
+    diagnostics: Vec<Diagnostic>,
+    loop_depth: usize,
+    block_depth: usize,
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, pos: 0 }
+    pub fn new(tokens: Vec<SpannedToken>) -> Self {
+        Parser {
+            tokens,
+            pos: 0,
+            diagnostics: Vec::new(),
+            loop_depth: 0,
+            block_depth: 0,
+        }
+    }
+
+    /// Parser to'plagan xatolarni qaytaradi.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
     }
 
     pub fn parse(&mut self) -> Vec<Stmt> {
         let mut stmts = Vec::new();
         while self.peek() != &Token::EOF {
-            if let Some(stmt) = self.parse_stmt() {
-                stmts.push(stmt);
-            } else {
-                // Skip token to avoid infinite loop on error
-                let token = self.advance();
-                eprintln!("Xatolik: Kutilmagan token: {:?}", token);
+            let start = self.pos;
+            match self.parse_stmt() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(diagnostic) => self.recover(diagnostic, start),
             }
         }
         stmts
     }
 
+    /// Xatoni yozib qo'yadi va xatoli qatorning qolgan tokenlarini tashlab yuboradi,
+    /// shunda bitta xato ketma-ket xatolar zanjirini keltirib chiqarmaydi.
+    fn recover(&mut self, diagnostic: Diagnostic, start: usize) {
+        self.diagnostics.push(diagnostic);
+        if self.pos == start {
+            self.advance();
+        }
+        let line = self.prev_span().line;
+        while self.peek() != &Token::EOF
+            && self.peek() != &Token::RBrace
+            && self.peek_span().line == line
+        {
+            self.advance();
+        }
+    }
+
     fn peek(&self) -> &Token {
         if self.pos < self.tokens.len() {
-            &self.tokens[self.pos]
+            &self.tokens[self.pos].token
         } else {
             &Token::EOF
         }
     }
 
+    fn peek_span(&self) -> Span {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map(|t| t.span)
+            .unwrap_or_default()
+    }
+
+    /// Oxirgi iste'mol qilingan tokenning joyi.
+    fn prev_span(&self) -> Span {
+        if self.pos > 0 {
+            self.tokens[self.pos - 1].span
+        } else {
+            self.peek_span()
+        }
+    }
+
+    fn peek_next(&self) -> &Token {
+        self.tokens
+            .get(self.pos + 1)
+            .map_or(&Token::EOF, |t| &t.token)
+    }
+
     fn advance(&mut self) -> &Token {
         if self.pos < self.tokens.len() {
             self.pos += 1;
         }
         if self.pos > 0 {
-            &self.tokens[self.pos - 1]
+            &self.tokens[self.pos - 1].token
         } else {
             &Token::EOF
         }
     }
 
-    fn parse_block(&mut self) -> Option<Vec<Stmt>> {
-        if let Token::LBrace = self.peek() {
-            self.advance(); // consume {
+    fn error<T>(&self, code: &'static str, message: impl Into<String>) -> ParseResult<T> {
+        Err(Diagnostic::error(code, message, self.peek_span()))
+    }
+
+    fn expect(&mut self, expected: Token, message: &str) -> ParseResult<Span> {
+        if self.peek() == &expected {
+            self.advance();
+            Ok(self.prev_span())
+        } else {
+            self.error(
+                "P002",
+                format!("{}, lekin {} topildi", message, self.peek()),
+            )
+        }
+    }
+
+    fn expect_identifier(&mut self, message: &str) -> ParseResult<String> {
+        if let Token::Identifier(name) = self.peek().clone() {
+            self.advance();
+            Ok(name)
         } else {
-            eprintln!("Xatolik: Blok {{ bilan boshlanishi kerak");
-            return None;
+            self.error(
+                "P002",
+                format!("{}, lekin {} topildi", message, self.peek()),
+            )
         }
+    }
+
+    fn parse_block(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.expect(Token::LBrace, "Blok '{' bilan boshlanishi kerak")?;
 
+        self.block_depth += 1;
         let mut stmts = Vec::new();
         while self.peek() != &Token::RBrace && self.peek() != &Token::EOF {
-            if let Some(stmt) = self.parse_stmt() {
-                stmts.push(stmt);
-            } else {
-                self.advance();
+            let start = self.pos;
+            match self.parse_stmt() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(diagnostic) => self.recover(diagnostic, start),
             }
         }
+        self.block_depth -= 1;
 
-        if let Token::RBrace = self.peek() {
-            self.advance(); // consume }
-            Some(stmts)
-        } else {
-            eprintln!("Xatolik: Blok }} bilan tugashi kerak");
-            None
+        self.expect(Token::RBrace, "Blok '}' bilan tugashi kerak")?;
+        Ok(stmts)
+    }
+
+    /// `funksiya` (va nomi) dan keyingi `(parametrlar) { tana }` qismi.
+    fn parse_function_rest(&mut self) -> ParseResult<Rc<FunctionDecl>> {
+        self.expect(
+            Token::LParen,
+            "Funksiya parametrlari '(' bilan boshlanishi kerak",
+        )?;
+        let mut params = Vec::new();
+        if self.peek() != &Token::RParen {
+            loop {
+                let param = self.expect_identifier("Parametr nomi kutilgan")?;
+                params.push(Rc::from(param.as_str()));
+
+                if self.peek() == &Token::Comma {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
         }
+        self.expect(Token::RParen, "')' kutilgan")?;
+        // to'xta/davom et funksiya tanasidan tashqaridagi tsiklga ta'sir qilmaydi
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.parse_block();
+        self.loop_depth = loop_depth;
+        Ok(Rc::new(FunctionDecl {
+            params,
+            body: body?,
+        }))
+    }
+
+    fn parse_loop_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.loop_depth += 1;
+        let body = self.parse_block();
+        self.loop_depth -= 1;
+        body
     }
 
-    fn parse_stmt(&mut self) -> Option<Stmt> {
+    fn parse_stmt(&mut self) -> ParseResult<Stmt> {
+        let start = self.peek_span();
         match self.peek() {
             Token::Yoz => {
                 self.advance();
                 let expr = self.parse_expr()?;
-                Some(Stmt::Print(expr))
+                Ok(Stmt::new(StmtKind::Print(expr), start))
             }
             Token::Agar => {
                 self.advance();
                 let condition = self.parse_expr()?;
                 let body = self.parse_block()?;
-                Some(Stmt::If(condition, body))
+                let mut branches = vec![(condition, body)];
+                let mut else_body = None;
+                loop {
+                    match self.peek() {
+                        Token::YokiAgar => {
+                            self.advance();
+                            let condition = self.parse_expr()?;
+                            let body = self.parse_block()?;
+                            branches.push((condition, body));
+                        }
+                        Token::AksHolda => {
+                            self.advance();
+                            else_body = Some(self.parse_block()?);
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+                Ok(Stmt::new(StmtKind::If(branches, else_body), start))
+            }
+            Token::Toxta | Token::DavomEt => {
+                if self.loop_depth == 0 {
+                    return self.error(
+                        "P006",
+                        format!("{} faqat tsikl ichida ishlatilishi mumkin", self.peek()),
+                    );
+                }
+                let kind = if self.advance() == &Token::Toxta {
+                    StmtKind::Break
+                } else {
+                    StmtKind::Continue
+                };
+                Ok(Stmt::new(kind, start))
             }
+            Token::AksHolda | Token::YokiAgar => self.error(
+                "P005",
+                format!("{} faqat 'agar' blokidan keyin kelishi mumkin", self.peek()),
+            ),
+            Token::UrinibKor => {
+                self.advance();
+                let body = self.parse_block()?;
+                self.expect(
+                    Token::XatoBolsa,
+                    "'urinib ko'r' blokidan keyin 'xato bo'lsa' kutilgan",
+                )?;
+                let name = match self.peek().clone() {
+                    Token::Identifier(name) => {
+                        self.advance();
+                        Some(name)
+                    }
+                    _ => None,
+                };
+                let handler = self.parse_block()?;
+                Ok(Stmt::new(StmtKind::Try(body, name, handler), start))
+            }
+            Token::XatoBolsa => self.error(
+                "P005",
+                format!(
+                    "{} faqat 'urinib ko'r' blokidan keyin kelishi mumkin",
+                    self.peek()
+                ),
+            ),
             Token::Takrorla => {
                 self.advance();
                 let condition = self.parse_expr()?;
-                let body = self.parse_block()?;
-                Some(Stmt::Loop(condition, body))
+                let body = self.parse_loop_body()?;
+                Ok(Stmt::new(StmtKind::Loop(condition, body), start))
             }
             Token::Uchun => {
                 self.advance(); // consume uchun
-                if let Token::Identifier(var_name) = self.advance().clone() {
-                    if let Token::Ichida = self.peek() {
-                        self.advance(); // consume ichida
-                        let collection = self.parse_expr()?;
-                        let body = self.parse_block()?;
-                        return Some(Stmt::For(var_name, collection, body));
-                    }
-                }
-                eprintln!(
-                    "Xatolik: For tsikli 'uchun <var> ichida <expr>' formatida bo'lishi kerak"
-                );
-                None
+                let message = "For tsikli 'uchun <var> ichida <expr>' formatida bo'lishi kerak";
+                let var_name = self.expect_identifier(message)?;
+                self.expect(Token::Ichida, message)?;
+                let collection = self.parse_expr()?;
+                let body = self.parse_loop_body()?;
+                Ok(Stmt::new(StmtKind::For(var_name, collection, body), start))
             }
-            Token::Funksiya => {
+            Token::Funksiya if matches!(self.peek_next(), Token::Identifier(_)) => {
                 self.advance();
-                if let Token::Identifier(name) = self.advance().clone() {
-                    if let Token::LParen = self.advance() {
-                        let mut params = Vec::new();
-                        if self.peek() != &Token::RParen {
-                            loop {
-                                if let Token::Identifier(param) = self.advance().clone() {
-                                    params.push(param);
-                                } else {
-                                    eprintln!("Xatolik: Parametr nomi kutilgan");
-                                    return None;
-                                }
-
-                                if self.peek() == &Token::Comma {
-                                    self.advance();
-                                } else {
-                                    break;
-                                }
-                            }
-                        }
-
-                        if let Token::RParen = self.advance() {
-                            let body = self.parse_block()?;
-                            return Some(Stmt::Function(name, params, body));
-                        }
-                    }
-                }
-                eprintln!("Xatolik: Funksiya deklaratsiyasi noto'g'ri");
-                None
+                let name = self.expect_identifier("Funksiya nomi kutilgan")?;
+                let decl = self.parse_function_rest()?;
+                Ok(Stmt::new(StmtKind::Function(name, decl), start))
             }
             Token::Qaytar => {
                 self.advance();
                 let expr = self.parse_expr()?;
-                Some(Stmt::Return(expr))
+                Ok(Stmt::new(StmtKind::Return(expr), start))
+            }
+            Token::Ulash => {
+                // Modul nomlari global doiraga yoziladi, shuning uchun bloklar ichida ruxsat yo'q
+                if self.block_depth > 0 {
+                    return self.error(
+                        "P007",
+                        "'ulash' faqat dasturning yuqori darajasida ishlatilishi mumkin",
+                    );
+                }
+                self.advance();
+                let Token::StringLiteral(path) = self.peek().clone() else {
+                    return self.error(
+                        "P002",
+                        format!(
+                            "'ulash' dan keyin fayl nomi kutilgan, lekin {} topildi",
+                            self.peek()
+                        ),
+                    );
+                };
+                self.advance();
+                let alias = if self.peek() == &Token::Sifatida {
+                    self.advance();
+                    Some(self.expect_identifier("'sifatida' dan keyin nom kutilgan")?)
+                } else {
+                    None
+                };
+                Ok(Stmt::new(StmtKind::Import(path, alias), start))
             }
             _ => {
                 // Expression statement or Assignment
                 let expr = self.parse_expr()?;
 
-                if let Token::Operator(op) = self.peek() {
-                    if op == "=" {
-                        self.advance(); // consume =
-                        let value = self.parse_expr()?;
+                if let Token::Assign = self.peek() {
+                    self.advance(); // consume =
+                    let value = self.parse_expr()?;
 
-                        match expr {
-                            Expr::Identifier(name) => return Some(Stmt::Assign(name, value)),
-                            Expr::Index(target, index) => {
-                                if let Expr::Identifier(name) = *target {
-                                    return Some(Stmt::AssignIndex(name, *index, value));
-                                } else {
-                                    eprintln!(
-                                        "Xatolik: Faqat o'zgaruvchilarga indeks orqali qiymat berish mumkin"
-                                    );
-                                    return None;
-                                }
-                            }
-                            _ => {
-                                eprintln!(
-                                    "Xatolik: Noto'g'ri o'zlashtirish, chap tarafda o'zgaruvchi bo'lishi kerak"
-                                );
-                                return None;
+                    return match expr.kind {
+                        ExprKind::Identifier(name) => {
+                            Ok(Stmt::new(StmtKind::Assign(name, value), start))
+                        }
+                        ExprKind::Index(target, index) => {
+                            if let ExprKind::Identifier(name) = target.kind {
+                                Ok(Stmt::new(StmtKind::AssignIndex(name, *index, value), start))
+                            } else {
+                                Err(Diagnostic::error(
+                                    "P003",
+                                    "Faqat o'zgaruvchilarga indeks orqali qiymat berish mumkin",
+                                    target.span,
+                                ))
                             }
                         }
-                    }
+                        _ => Err(Diagnostic::error(
+                            "P003",
+                            "Noto'g'ri o'zlashtirish, chap tarafda o'zgaruvchi bo'lishi kerak",
+                            expr.span,
+                        )),
+                    };
                 }
 
-                Some(Stmt::Expr(expr))
+                Ok(Stmt::new(StmtKind::Expr(expr), start))
             }
         }
     }
 
-    fn parse_expr(&mut self) -> Option<Expr> {
+    fn parse_expr(&mut self) -> ParseResult<Expr> {
         self.parse_logical_or()
     }
 
-    fn parse_logical_or(&mut self) -> Option<Expr> {
+    fn binary(left: Expr, op: BinOp, right: Expr) -> Expr {
+        let span = left.span.to(right.span);
+        Expr::new(
+            ExprKind::BinaryOp(Box::new(left), op, Box::new(right)),
+            span,
+        )
+    }
+
+    fn parse_logical_or(&mut self) -> ParseResult<Expr> {
         let mut left = self.parse_logical_and()?;
 
         while let Token::Or = self.peek() {
             self.advance();
             let right = self.parse_logical_and()?;
-            left = Expr::BinaryOp(Box::new(left), "||".to_string(), Box::new(right));
+            left = Self::binary(left, BinOp::Or, right);
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse_logical_and(&mut self) -> Option<Expr> {
+    fn parse_logical_and(&mut self) -> ParseResult<Expr> {
         let mut left = self.parse_comparison()?;
 
         while let Token::And = self.peek() {
             self.advance();
             let right = self.parse_comparison()?;
-            left = Expr::BinaryOp(Box::new(left), "&&".to_string(), Box::new(right));
+            left = Self::binary(left, BinOp::And, right);
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse_comparison(&mut self) -> Option<Expr> {
+    fn parse_comparison(&mut self) -> ParseResult<Expr> {
         let mut left = self.parse_term()?;
 
-        while let Token::Operator(op) = self.peek().clone() {
-            if ["==", "!=", "<", ">", "<=", ">="].contains(&op.as_str()) {
-                self.advance();
-                let right = self.parse_term()?;
-                left = Expr::BinaryOp(Box::new(left), op, Box::new(right));
-            } else {
-                break;
-            }
+        loop {
+            let op = match self.peek() {
+                Token::EqEq => BinOp::Eq,
+                Token::NotEq => BinOp::NotEq,
+                Token::Less => BinOp::Lt,
+                Token::Greater => BinOp::Gt,
+                Token::LessEq => BinOp::LtEq,
+                Token::GreaterEq => BinOp::GtEq,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_term()?;
+            left = Self::binary(left, op, right);
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse_term(&mut self) -> Option<Expr> {
+    fn parse_term(&mut self) -> ParseResult<Expr> {
         let mut left = self.parse_factor()?;
 
-        while let Token::Operator(op) = self.peek().clone() {
-            if ["+", "-"].contains(&op.as_str()) {
-                self.advance();
-                let right = self.parse_factor()?;
-                left = Expr::BinaryOp(Box::new(left), op, Box::new(right));
-            } else {
-                break;
-            }
+        loop {
+            let op = match self.peek() {
+                Token::Plus => BinOp::Add,
+                Token::Minus => BinOp::Sub,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_factor()?;
+            left = Self::binary(left, op, right);
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse_factor(&mut self) -> Option<Expr> {
+    fn parse_factor(&mut self) -> ParseResult<Expr> {
         let mut left = self.parse_unary()?;
 
-        while let Token::Operator(op) = self.peek().clone() {
-            if ["*", "/"].contains(&op.as_str()) {
-                self.advance();
-                let right = self.parse_unary()?;
-                left = Expr::BinaryOp(Box::new(left), op, Box::new(right));
-            } else {
-                break;
-            }
+        loop {
+            let op = match self.peek() {
+                Token::Star => BinOp::Mul,
+                Token::Slash => BinOp::Div,
+                Token::Percent => BinOp::Mod,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_unary()?;
+            left = Self::binary(left, op, right);
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse_unary(&mut self) -> Option<Expr> {
+    fn parse_unary(&mut self) -> ParseResult<Expr> {
         if let Token::Not = self.peek() {
+            let start = self.peek_span();
             self.advance();
             let right = self.parse_unary()?;
-            Some(Expr::UnaryOp("!".to_string(), Box::new(right)))
+            let span = start.to(right.span);
+            Ok(Expr::new(
+                ExprKind::UnaryOp(UnOp::Not, Box::new(right)),
+                span,
+            ))
         } else {
-            self.parse_postfix()
+            self.parse_power()
         }
     }
 
-    fn parse_postfix(&mut self) -> Option<Expr> {
+    /// `**` boshqa amallardan kuchliroq bog'lanadi va o'ngdan chapga guruhlanadi:
+    /// `2 ** 3 ** 2` bu `2 ** (3 ** 2)`.
+    fn parse_power(&mut self) -> ParseResult<Expr> {
+        let base = self.parse_postfix()?;
+        if self.peek() != &Token::Power {
+            return Ok(base);
+        }
+        self.advance();
+        let exponent = self.parse_unary()?;
+        Ok(Self::binary(base, BinOp::Pow, exponent))
+    }
+
+    fn parse_postfix(&mut self) -> ParseResult<Expr> {
         let mut left = self.parse_primary()?;
 
         loop {
@@ -290,73 +577,80 @@ impl Parser {
                 Token::LBracket => {
                     self.advance(); // consume [
                     let index = self.parse_expr()?;
-                    if let Token::RBracket = self.advance() {
-                        left = Expr::Index(Box::new(left), Box::new(index));
-                    } else {
-                        eprintln!("Xatolik: ] kutilgan");
-                        return None;
-                    }
+                    let end = self.expect(Token::RBracket, "']' kutilgan")?;
+                    let span = left.span.to(end);
+                    left = Expr::new(ExprKind::Index(Box::new(left), Box::new(index)), span);
+                }
+                Token::Dot => {
+                    // m.nom is shorthand for m["nom"]
+                    self.advance(); // consume .
+                    let key_span = self.peek_span();
+                    let key = self.expect_identifier("'.' dan keyin nom kutilgan")?;
+                    let key = Expr::new(ExprKind::StringLiteral(key), key_span);
+                    let span = left.span.to(key_span);
+                    left = Expr::new(ExprKind::Index(Box::new(left), Box::new(key)), span);
                 }
                 Token::LParen => {
-                    // Function Call
-                    if let Expr::Identifier(name) = left {
-                        self.advance(); // consume (
-                        let mut args = Vec::new();
-                        if self.peek() != &Token::RParen {
-                            loop {
-                                if let Some(arg) = self.parse_expr() {
-                                    args.push(arg);
-                                } else {
-                                    break;
-                                }
-
-                                if self.peek() == &Token::Comma {
-                                    self.advance();
-                                } else {
-                                    break;
-                                }
+                    // Function Call: callee can be any expression, e.g. f(1)(2) or amallar[0](x)
+                    self.advance(); // consume (
+                    let mut args = Vec::new();
+                    if self.peek() != &Token::RParen {
+                        loop {
+                            args.push(self.parse_expr()?);
+
+                            if self.peek() == &Token::Comma {
+                                self.advance();
+                            } else {
+                                break;
                             }
                         }
-
-                        if let Token::RParen = self.advance() {
-                            left = Expr::Call(name, args);
-                        } else {
-                            eprintln!("Xatolik: ) kutilgan");
-                            return None;
-                        }
-                    } else {
-                        // Call on non-identifier (e.g. (func())()) - not supported for now as Call takes String
-                        // Or grouping? No, grouping is handled in parse_primary
-                        eprintln!("Xatolik: Faqat funksiya nomini chaqirish mumkin");
-                        return None;
                     }
+
+                    let end = self.expect(Token::RParen, "')' kutilgan")?;
+                    let span = left.span.to(end);
+                    left = Expr::new(ExprKind::Call(Box::new(left), args), span);
                 }
                 _ => break,
             }
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse_primary(&mut self) -> Option<Expr> {
+    fn parse_primary(&mut self) -> ParseResult<Expr> {
+        let start = self.peek_span();
         match self.peek() {
             Token::Number(n) => {
                 let n = *n;
                 self.advance();
-                Some(Expr::Number(n))
+                Ok(Expr::new(ExprKind::Number(n), start))
+            }
+            Token::Float(n) => {
+                let n = *n;
+                self.advance();
+                Ok(Expr::new(ExprKind::Float(n), start))
             }
             Token::StringLiteral(s) => {
                 let s = s.clone();
                 self.advance();
-                Some(Expr::StringLiteral(s))
+                Ok(Expr::new(ExprKind::StringLiteral(s), start))
+            }
+            Token::Rost | Token::Yolgon => {
+                let value = self.advance() == &Token::Rost;
+                Ok(Expr::new(ExprKind::Bool(value), start))
             }
             Token::Identifier(s) => {
                 let s = s.clone();
                 self.advance();
-                Some(Expr::Identifier(s))
+                Ok(Expr::new(ExprKind::Identifier(s), start))
             }
             Token::Sora => {
                 self.advance();
-                Some(Expr::Input)
+                Ok(Expr::new(ExprKind::Input, start))
+            }
+            Token::Funksiya => {
+                self.advance();
+                let decl = self.parse_function_rest()?;
+                Ok(Expr::new(ExprKind::Function(decl), start))
             }
             Token::LBracket => {
                 self.advance(); // consume [
@@ -371,24 +665,220 @@ impl Parser {
                         }
                     }
                 }
-                if let Token::RBracket = self.advance() {
-                    Some(Expr::Array(elements))
-                } else {
-                    eprintln!("Xatolik: ] kutilgan");
-                    None
+                let end = self.expect(Token::RBracket, "']' kutilgan")?;
+                Ok(Expr::new(ExprKind::Array(elements), start.to(end)))
+            }
+            Token::LBrace => {
+                self.advance(); // consume {
+                let mut entries = Vec::new();
+                if self.peek() != &Token::RBrace {
+                    loop {
+                        let key = self.parse_expr()?;
+                        self.expect(Token::Colon, "Lug'at kalitidan keyin ':' kutilgan")?;
+                        let value = self.parse_expr()?;
+                        entries.push((key, value));
+                        if self.peek() == &Token::Comma {
+                            self.advance();
+                        } else {
+                            break;
+                        }
+                    }
                 }
+                let end = self.expect(Token::RBrace, "'}' kutilgan")?;
+                Ok(Expr::new(ExprKind::Dict(entries), start.to(end)))
             }
             Token::LParen => {
                 self.advance(); // consume (
                 let expr = self.parse_expr()?;
-                if let Token::RParen = self.advance() {
-                    Some(expr)
-                } else {
-                    eprintln!("Xatolik: ) kutilgan");
-                    None
-                }
+                self.expect(Token::RParen, "')' kutilgan")?;
+                Ok(expr)
             }
-            _ => None,
+            token => self.error("P001", format!("Ifoda kutilgan, lekin {} topildi", token)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn parse(input: &str) -> (Vec<Stmt>, Vec<Diagnostic>) {
+        let tokens = Lexer::new(input).tokenize();
+        let mut parser = Parser::new(tokens);
+        let stmts = parser.parse();
+        (stmts, parser.take_diagnostics())
+    }
+
+    #[test]
+    fn test_stmt_spans() {
+        let (stmts, diagnostics) = parse("yoz 1\nagar x > 2 {\n  y = [1, 2]\n}");
+        assert!(diagnostics.is_empty());
+        assert_eq!(stmts[0].span, Span::new(1, 1, 3));
+        assert_eq!(stmts[1].span, Span::new(2, 1, 4));
+        let StmtKind::If(branches, _) = &stmts[1].kind else {
+            panic!("agar kutilgan");
+        };
+        let (cond, body) = &branches[0];
+        assert_eq!(cond.span, Span::new(2, 6, 5));
+        let StmtKind::Assign(_, value) = &body[0].kind else {
+            panic!("o'zlashtirish kutilgan");
+        };
+        assert_eq!(value.span, Span::new(3, 7, 6));
+    }
+
+    #[test]
+    fn test_error_reports_position_and_recovers() {
+        let (stmts, diagnostics) = parse("yoz 1 +\nyoz 2\nx = )\nyoz 3");
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code, "P001");
+        assert_eq!(diagnostics[0].span, Span::new(2, 1, 3));
+        assert_eq!(diagnostics[1].span, Span::new(3, 5, 1));
+        // Only the broken lines are dropped
+        assert_eq!(stmts.len(), 2);
+    }
+
+    #[test]
+    fn test_unclosed_block() {
+        let (_, diagnostics) = parse("agar 1 {\n yoz 1\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.starts_with("Blok '}'"));
+    }
+
+    #[test]
+    fn test_else_if_chain() {
+        let (stmts, diagnostics) = parse(
+            "agar x { yoz 1 } yoki agar y { yoz 2 } yoki agar z { yoz 3 } aks holda { yoz 4 }",
+        );
+        assert!(diagnostics.is_empty());
+        let StmtKind::If(branches, else_body) = &stmts[0].kind else {
+            panic!("agar kutilgan");
+        };
+        assert_eq!(branches.len(), 3);
+        assert_eq!(else_body.as_ref().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn test_dangling_else() {
+        let (_, diagnostics) = parse("aks holda { yoz 1 }");
+        assert_eq!(diagnostics[0].code, "P005");
+    }
+
+    #[test]
+    fn test_try_statement() {
+        let (stmts, diagnostics) = parse(
+            "urinib ko'r { yoz 1 } xato bo'lsa e { yoz e }\nurinib ko'r {\n} xato bo'lsa { }",
+        );
+        assert!(diagnostics.is_empty());
+        assert!(matches!(
+            &stmts[0].kind,
+            StmtKind::Try(body, Some(name), handler)
+                if body.len() == 1 && name == "e" && handler.len() == 1
+        ));
+        assert!(matches!(&stmts[1].kind, StmtKind::Try(_, None, _)));
+
+        let (_, diagnostics) = parse("urinib ko'r { yoz 1 }\nyoz 2");
+        assert_eq!(diagnostics[0].code, "P002");
+        let (_, diagnostics) = parse("xato bo'lsa { yoz 1 }");
+        assert_eq!(diagnostics[0].code, "P005");
+    }
+
+    #[test]
+    fn test_break_outside_loop() {
+        let (stmts, diagnostics) = parse("takrorla 1 { to'xta }\nto'xta");
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "P006");
+
+        let (_, diagnostics) = parse("takrorla 1 {\n funksiya f() { davom et }\n}");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Span::new(2, 17, 8));
+    }
+
+    #[test]
+    fn test_call_on_any_expression() {
+        let (stmts, diagnostics) = parse("x = f(1)(2)\ng = funksiya(a) { qaytar a }");
+        assert!(diagnostics.is_empty());
+        let StmtKind::Assign(_, value) = &stmts[0].kind else {
+            panic!("o'zlashtirish kutilgan");
+        };
+        let ExprKind::Call(callee, args) = &value.kind else {
+            panic!("chaqiruv kutilgan");
+        };
+        assert_eq!(args.len(), 1);
+        assert!(matches!(callee.kind, ExprKind::Call(_, _)));
+        let StmtKind::Assign(_, value) = &stmts[1].kind else {
+            panic!("o'zlashtirish kutilgan");
+        };
+        assert!(matches!(&value.kind, ExprKind::Function(decl) if decl.params.len() == 1));
+    }
+
+    #[test]
+    fn test_operators_are_typed() {
+        let (stmts, diagnostics) = parse("x = 1 + 2 * 3 >= 4 && !y");
+        assert!(diagnostics.is_empty());
+        let StmtKind::Assign(_, value) = &stmts[0].kind else {
+            panic!("o'zlashtirish kutilgan");
+        };
+        let ExprKind::BinaryOp(cmp, BinOp::And, not) = &value.kind else {
+            panic!("'&&' kutilgan");
+        };
+        assert!(matches!(not.kind, ExprKind::UnaryOp(UnOp::Not, _)));
+        let ExprKind::BinaryOp(sum, BinOp::GtEq, _) = &cmp.kind else {
+            panic!("'>=' kutilgan");
+        };
+        assert!(matches!(
+            &sum.kind,
+            ExprKind::BinaryOp(_, BinOp::Add, product)
+                if matches!(product.kind, ExprKind::BinaryOp(_, BinOp::Mul, _))
+        ));
+    }
+
+    #[test]
+    fn test_power_and_modulo_precedence() {
+        let (stmts, diagnostics) = parse("x = 1 + 2 * 3 % 4 ** 2 ** 3");
+        assert!(diagnostics.is_empty());
+        let StmtKind::Assign(_, value) = &stmts[0].kind else {
+            panic!("o'zlashtirish kutilgan");
+        };
+        let ExprKind::BinaryOp(_, BinOp::Add, modulo) = &value.kind else {
+            panic!("'+' kutilgan");
+        };
+        // (2 * 3) % (4 ** (2 ** 3))
+        let ExprKind::BinaryOp(product, BinOp::Mod, power) = &modulo.kind else {
+            panic!("'%' kutilgan");
+        };
+        assert!(matches!(product.kind, ExprKind::BinaryOp(_, BinOp::Mul, _)));
+        let ExprKind::BinaryOp(base, BinOp::Pow, exponent) = &power.kind else {
+            panic!("'**' kutilgan");
+        };
+        assert!(matches!(base.kind, ExprKind::Number(4)));
+        assert!(matches!(
+            exponent.kind,
+            ExprKind::BinaryOp(_, BinOp::Pow, _)
+        ));
+    }
+
+    #[test]
+    fn test_import_statement() {
+        let (stmts, diagnostics) = parse("ulash \"a.uz\"\nulash \"b.uz\" sifatida b\nyoz b.f(1)");
+        assert!(diagnostics.is_empty());
+        assert!(matches!(&stmts[0].kind, StmtKind::Import(path, None) if path == "a.uz"));
+        assert!(matches!(&stmts[1].kind, StmtKind::Import(_, Some(alias)) if alias == "b"));
+        let StmtKind::Print(call) = &stmts[2].kind else {
+            panic!("yoz kutilgan");
+        };
+        let ExprKind::Call(callee, _) = &call.kind else {
+            panic!("chaqiruv kutilgan");
+        };
+        assert!(matches!(
+            &callee.kind,
+            ExprKind::Index(_, key) if matches!(&key.kind, ExprKind::StringLiteral(k) if k == "f")
+        ));
+
+        let (_, diagnostics) = parse("funksiya f() {\n  ulash \"a.uz\"\n}");
+        assert_eq!(diagnostics[0].code, "P007");
+        let (_, diagnostics) = parse("ulash a");
+        assert_eq!(diagnostics[0].code, "P002");
+    }
+}
@@ -0,0 +1,285 @@
+use crate::interpreter::{Engine, Interpreter, Value};
+use crate::lexer::{Lexer, Token, normalize_apostrophe};
+use crate::net::NetworkPolicy;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use std::env;
+use std::path::PathBuf;
+
+const FILENAME: &str = "<repl>";
+const PROMPT: &str = ">>> ";
+const CONTINUATION_PROMPT: &str = "... ";
+
+const HELP: &str = "\
+Buyruqlar:
+  :yordam           shu yordamni ko'rsatish
+  :yordam <nom>     standart funksiya haqida ma'lumot
+  :o'zgaruvchilar   global o'zgaruvchilar va ularning qiymatlari
+  :tozala           barcha o'zgaruvchi va funksiyalarni o'chirish
+  Ctrl+D            chiqish
+
+Ifoda kiritilsa, uning qiymati chiqariladi:
+  >>> 2 + 3
+  5
+'{' bilan ochilgan blok '}' bilan yopilguncha keyingi qatorlarda davom etadi.";
+
+/// Interaktiv rejim holati: barcha qatorlar bitta interpreterda bajariladi.
+pub struct Repl {
+    interpreter: Interpreter,
+    engine: Engine,
+    network: NetworkPolicy,
+    file_root: Option<PathBuf>,
+}
+
+fn new_interpreter(
+    engine: Engine,
+    network: &NetworkPolicy,
+    file_root: Option<&PathBuf>,
+) -> Interpreter {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_engine(engine);
+    interpreter.set_network_policy(network.clone());
+    if let Some(root) = file_root {
+        interpreter.set_file_root(root);
+    }
+    interpreter
+}
+
+impl Repl {
+    pub fn new(engine: Engine) -> Self {
+        let network = NetworkPolicy::default();
+        Repl {
+            interpreter: new_interpreter(engine, &network, None),
+            engine,
+            network,
+            file_root: None,
+        }
+    }
+
+    /// Tarmoq siyosatini belgilaydi; `:tozala` dan keyin ham saqlanadi.
+    pub fn set_network_policy(&mut self, policy: NetworkPolicy) {
+        self.interpreter.set_network_policy(policy.clone());
+        self.network = policy;
+    }
+
+    /// Fayl funksiyalari va `ulash` ishlaydigan papkani belgilaydi; `:tozala` dan keyin ham
+    /// saqlanadi.
+    pub fn set_file_root(&mut self, root: impl Into<PathBuf>) {
+        let root = root.into();
+        self.interpreter.set_file_root(&root);
+        self.file_root = Some(root);
+    }
+
+    /// Kiritilgan kodni bajaradi. Oxirgi ifodaning qiymatini qaytaradi,
+    /// xato bo'lsa uni manba qatori bilan tayyor matn sifatida qaytaradi.
+    pub fn eval(&mut self, source: &str) -> Result<Option<Value>, String> {
+        let result = self.interpreter.eval_source(source);
+        for diagnostic in self.interpreter.take_diagnostics() {
+            eprint!("{}", diagnostic.render(source, FILENAME));
+        }
+        result.map_err(|err| err.render(source, FILENAME))
+    }
+
+    /// `:` bilan boshlanadigan buyruqni bajaradi va chiqariladigan matnni qaytaradi.
+    pub fn command(&mut self, line: &str) -> String {
+        let command: String = line.trim().chars().map(normalize_apostrophe).collect();
+        if let Some(name) = command.strip_prefix(":yordam ") {
+            return match self.interpreter.native(name.trim()) {
+                Some(native) => native.doc.to_string(),
+                None => format!("'{}' nomli standart funksiya yo'q", name.trim()),
+            };
+        }
+        match command.as_str() {
+            ":yordam" => {
+                let names: Vec<&str> = self
+                    .interpreter
+                    .natives()
+                    .into_iter()
+                    .map(|native| native.name.as_ref())
+                    .collect();
+                format!("{}\n\nStandart funksiyalar: {}", HELP, names.join(", "))
+            }
+            ":o'zgaruvchilar" => {
+                let globals = self.interpreter.globals();
+                if globals.is_empty() {
+                    return "O'zgaruvchilar yo'q".to_string();
+                }
+                globals
+                    .iter()
+                    .map(|(name, val)| format!("{} = {}", name, val))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            ":tozala" => {
+                self.interpreter =
+                    new_interpreter(self.engine, &self.network, self.file_root.as_ref());
+                "Barcha o'zgaruvchilar o'chirildi".to_string()
+            }
+            other => format!(
+                "Noma'lum buyruq: {}. Buyruqlar ro'yxati uchun :yordam",
+                other
+            ),
+        }
+    }
+}
+
+/// Blok hali yopilmagan bo'lsa, keyingi qatorni kutish kerak.
+pub fn is_incomplete(source: &str) -> bool {
+    let mut depth = 0i64;
+    for spanned in Lexer::new(source).tokenize() {
+        match spanned.token {
+            Token::LBrace => depth += 1,
+            Token::RBrace => depth -= 1,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".uzlang_tarix"))
+}
+
+/// Interaktiv rejimni ishga tushiradi va Ctrl+D bosilguncha qatorlarni o'qiydi.
+pub fn run(engine: Engine, network: NetworkPolicy, file_root: Option<String>) {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("Terminalni ochib bo'lmadi: {}", e);
+            return;
+        }
+    };
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file is normal on the first run
+        let _ = editor.load_history(path);
+    }
+
+    println!(
+        "UzLang {} interaktiv rejimi. Yordam uchun :yordam, chiqish uchun Ctrl+D",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let mut repl = Repl::new(engine);
+    repl.set_network_policy(network);
+    if let Some(root) = file_root {
+        repl.set_file_root(root);
+    }
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            // Ctrl+C tugallanmagan blokni bekor qiladi
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Qatorni o'qishda xatolik: {}", e);
+                break;
+            }
+        };
+
+        if buffer.is_empty() && line.trim_start().starts_with(':') {
+            let _ = editor.add_history_entry(line.as_str());
+            println!("{}", repl.command(&line));
+            continue;
+        }
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if is_incomplete(&buffer) {
+            continue;
+        }
+
+        let source = std::mem::take(&mut buffer);
+        if source.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(source.trim_end());
+        match repl.eval(&source) {
+            Ok(Some(val)) => println!("{}", val),
+            Ok(None) => {}
+            Err(rendered) => eprint!("{}", rendered),
+        }
+    }
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_persists_between_inputs() {
+        let mut repl = Repl::new(Engine::default());
+        assert_eq!(repl.eval("x = 2"), Ok(None));
+        assert_eq!(repl.eval("funksiya f(a) { qaytar a * x }"), Ok(None));
+        assert_eq!(repl.eval("f(5)"), Ok(Some(Value::Number(10))));
+        assert_eq!(repl.eval("x = x + 1\nx"), Ok(Some(Value::Number(3))));
+    }
+
+    #[test]
+    fn test_errors_do_not_lose_state() {
+        let mut repl = Repl::new(Engine::default());
+        repl.eval("x = 1").unwrap();
+        let err = repl.eval("y = yoq").unwrap_err();
+        assert!(err.contains("R001"));
+        assert!(repl.eval("x = )").unwrap_err().contains("P001"));
+        assert_eq!(repl.eval("x"), Ok(Some(Value::Number(1))));
+    }
+
+    #[test]
+    fn test_incomplete_blocks() {
+        assert!(is_incomplete("agar 1 {\n"));
+        assert!(is_incomplete("funksiya f() {\n  agar 1 {\n  }\n"));
+        assert!(!is_incomplete("agar 1 {\n}\n"));
+        assert!(!is_incomplete("yoz \"{\"\n"));
+    }
+
+    #[test]
+    fn test_meta_commands() {
+        let mut repl = Repl::new(Engine::default());
+        assert!(repl.command(":yordam").contains(":tozala"));
+        assert!(repl.command(":yordam").contains("uzunlik"));
+        assert!(repl.command(":yordam son").starts_with("son(qiymat)"));
+        assert!(repl.command(":yordam yoq").contains("yo'q"));
+        assert_eq!(repl.command(":o'zgaruvchilar"), "O'zgaruvchilar yo'q");
+        repl.eval("b = [1, 2]\na = \"salom\"").unwrap();
+        assert_eq!(repl.command(":o'zgaruvchilar"), "a = salom\nb = [1; 2]");
+        assert_eq!(repl.command(":o‘zgaruvchilar"), "a = salom\nb = [1; 2]");
+        repl.command(":tozala");
+        assert!(repl.eval("a").is_err());
+        assert!(repl.command(":nima").starts_with("Noma'lum buyruq"));
+    }
+
+    #[test]
+    fn test_file_root_survives_reset() {
+        let root = std::env::temp_dir().join(format!("uzlang_repl_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.txt"), "ildizda").unwrap();
+
+        let mut repl = Repl::new(Engine::default());
+        repl.set_file_root(&root);
+        assert_eq!(
+            repl.eval("fayl_oqi(\"a.txt\")"),
+            Ok(Some(Value::from("ildizda")))
+        );
+        repl.command(":tozala");
+        assert_eq!(
+            repl.eval("fayl_oqi(\"a.txt\")"),
+            Ok(Some(Value::from("ildizda")))
+        );
+        assert!(repl.eval("fayl_oqi(\"../a.txt\")").is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
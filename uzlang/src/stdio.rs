@@ -0,0 +1,79 @@
+use crate::interpreter::{Interpreter, Value};
+use std::cell::RefCell;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+
+/// `yoz` va `so'ra` qayerga yozib, qayerdan o'qishi.
+pub(crate) struct Stdio {
+    output: Box<dyn Write>,
+    // None reads the process stdin; it is not locked permanently so the REPL can share it
+    input: Option<Box<dyn BufRead>>,
+}
+
+impl Default for Stdio {
+    fn default() -> Self {
+        Stdio {
+            output: Box::new(io::stdout()),
+            input: None,
+        }
+    }
+}
+
+/// Dastur chiqishini xotirada yig'adi. Nusxalari bitta buferni bo'lishadi,
+/// shuning uchun bittasini interpreterga berib, ikkinchisidan natijani o'qish mumkin.
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl CapturedOutput {
+    pub fn new() -> Self {
+        CapturedOutput::default()
+    }
+
+    /// Hozirgacha yozilgan matn.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Interpreter {
+    /// `yoz` natijasi shu yerga yoziladi (standart: stdout).
+    pub fn set_output(&mut self, output: impl Write + 'static) {
+        self.stdio.output = Box::new(output);
+    }
+
+    /// `so'ra` shu yerdan qator o'qiydi (standart: stdin).
+    pub fn set_input(&mut self, input: impl BufRead + 'static) {
+        self.stdio.input = Some(Box::new(input));
+    }
+
+    pub(crate) fn print(&mut self, val: &Value) {
+        // A closed pipe should not abort the program halfway through
+        let _ = writeln!(self.stdio.output, "{}", val);
+    }
+
+    /// `so'ra` uchun bitta qator o'qiydi. Kirish tugagan bo'lsa, bo'sh matn qaytadi.
+    pub(crate) fn read_input(&mut self) -> Value {
+        let _ = self.stdio.output.flush();
+        let mut line = String::new();
+        let read = match &mut self.stdio.input {
+            Some(input) => input.read_line(&mut line),
+            None => io::stdin().read_line(&mut line),
+        };
+        if read.is_ok() {
+            Value::String(Rc::from(line.trim()))
+        } else {
+            Value::empty_string()
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::interpreter::{Interpreter, RuntimeError, RuntimeErrorKind, Value};
+use crate::native::NativeArgs;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+/// `kesish` chegarasi: `0 <= indeks <= uzunlik` bo'lishi kerak.
+fn bound(args: &NativeArgs, index: usize, len: usize) -> Result<usize, RuntimeError> {
+    let n = args.number(index)?;
+    match usize::try_from(n) {
+        Ok(bound) if bound <= len => Ok(bound),
+        _ => Err(RuntimeError::new(
+            RuntimeErrorKind::IndexOutOfBounds { index: n, len },
+            args.span(index),
+        )),
+    }
+}
+
+fn kesish(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let len = match args.get(0) {
+        Value::String(s) => s.chars().count(),
+        Value::Array(elements) => elements.len(),
+        other => {
+            return Err(RuntimeError::type_mismatch(
+                format!(
+                    "'kesish' funksiyasining 1-parametri matn yoki massiv bo'lishi kerak, {} berildi",
+                    other.type_name()
+                ),
+                args.span(0),
+            ));
+        }
+    };
+    let start = bound(args, 1, len)?;
+    let end = bound(args, 2, len)?;
+    if start > end {
+        return Err(RuntimeError::type_mismatch(
+            format!(
+                "Kesish boshi ({}) oxiridan ({}) katta bo'lmasligi kerak",
+                start, end
+            ),
+            args.span,
+        ));
+    }
+    Ok(match args.get(0) {
+        Value::String(s) => {
+            Value::from(s.chars().skip(start).take(end - start).collect::<String>())
+        }
+        Value::Array(elements) => Value::from(elements[start..end].to_vec()),
+        _ => unreachable!("checked above"),
+    })
+}
+
+fn bol(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let text = args.string(0)?;
+    let separator = args.string(1)?;
+    // Bo'sh ajratgich matnni harflarga bo'ladi
+    let parts: Vec<Value> = if separator.is_empty() {
+        text.chars().map(|c| Value::from(c.to_string())).collect()
+    } else {
+        text.split(&**separator).map(Value::from).collect()
+    };
+    Ok(Value::from(parts))
+}
+
+fn birlashtir(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let parts: Vec<String> = args.array(0)?.iter().map(Value::to_string).collect();
+    Ok(Value::from(parts.join(args.string(1)?)))
+}
+
+fn almashtir(_: &mut Interpreter, args: &NativeArgs) -> NativeResult {
+    let text = args.string(0)?;
+    let old = args.string(1)?;
+    if old.is_empty() {
+        return Err(RuntimeError::type_mismatch(
+            "'almashtir' funksiyasida almashtiriladigan matn bo'sh bo'lmasligi kerak",
+            args.span(1),
+        ));
+    }
+    Ok(Value::from(text.replace(&**old, args.string(2)?)))
+}
+
+/// Matn bilan ishlash funksiyalari.
+pub(crate) fn register(interpreter: &mut Interpreter) {
+    interpreter.register_native(
+        "kesish",
+        3,
+        "kesish(qiymat, boshi, oxiri): matn yoki massivning [boshi, oxiri) oralig'idagi qismi",
+        kesish,
+    );
+    interpreter.register_native(
+        "bo'l",
+        2,
+        "bo'l(matn, ajratgich): matnni ajratgich bo'yicha massivga bo'ladi",
+        bol,
+    );
+    interpreter.register_native(
+        "birlashtir",
+        2,
+        "birlashtir(massiv, ajratgich): elementlarni ajratgich bilan bitta matnga qo'shadi",
+        birlashtir,
+    );
+    interpreter.register_native(
+        "almashtir",
+        3,
+        "almashtir(matn, eski, yangi): matndagi barcha 'eski' qismlarni 'yangi' bilan almashtiradi",
+        almashtir,
+    );
+    interpreter.register_native(
+        "ichidami",
+        2,
+        "ichidami(matn, qism): matnda shu qism bor-yo'qligi",
+        |_, args| Ok(Value::Bool(args.string(0)?.contains(&**args.string(1)?))),
+    );
+    interpreter.register_native(
+        "boshlanadimi",
+        2,
+        "boshlanadimi(matn, qism): matn shu qism bilan boshlanadimi",
+        |_, args| Ok(Value::Bool(args.string(0)?.starts_with(&**args.string(1)?))),
+    );
+    interpreter.register_native(
+        "tugaydimi",
+        2,
+        "tugaydimi(matn, qism): matn shu qism bilan tugaydimi",
+        |_, args| Ok(Value::Bool(args.string(0)?.ends_with(&**args.string(1)?))),
+    );
+    interpreter.register_native(
+        "tozala",
+        1,
+        "tozala(matn): boshidagi va oxiridagi bo'shliqlarni olib tashlaydi",
+        |_, args| Ok(Value::from(args.string(0)?.trim())),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::test_support::answer as run;
+
+    fn text(code: &str) -> String {
+        run(code).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_length_and_indexing_use_characters() {
+        assert_eq!(text("javob = uzunlik(\"o‘zbek\")"), "6");
+        assert_eq!(text("s = \"салом\"\njavob = [s[0], s[4]]"), "[с; м]");
+        let err = run("javob = \"abc\"[3]").unwrap_err();
+        assert_eq!(
+            err.kind.to_string(),
+            "Indeks chegaradan tashqarida: 3 (uzunlik 3)"
+        );
+    }
+
+    #[test]
+    fn test_slicing() {
+        assert_eq!(text("javob = kesish(\"g'alaba\", 3, 6)"), "lab");
+        assert_eq!(text("javob = kesish([1, 2, 3], 1, 3)"), "[2; 3]");
+        assert_eq!(text("javob = kesish(\"тошкент\", 0, 0)"), "");
+        let err = run("javob = kesish(\"abc\", 1, 4)").unwrap_err();
+        assert_eq!(err.kind.code(), "R004");
+        assert_eq!(err.span.column, 26);
+        assert_eq!(
+            run("javob = kesish(\"abc\", 2, 1)")
+                .unwrap_err()
+                .kind
+                .code(),
+            "R003"
+        );
+    }
+
+    #[test]
+    fn test_split_join_replace() {
+        assert_eq!(
+            text("javob = bo'l(\"olma,nok,,uzum\", \",\")"),
+            "[olma; nok; ; uzum]"
+        );
+        assert_eq!(text("javob = bo‘l(\"abc\", \"\")"), "[a; b; c]");
+        assert_eq!(
+            text("javob = birlashtir([1, \"ikki\", 3], \" - \")"),
+            "1 - ikki - 3"
+        );
+        assert_eq!(text("javob = almashtir(\"a-b-c\", \"-\", \"+\")"), "a+b+c");
+        assert!(run("javob = almashtir(\"abc\", \"\", \"x\")").is_err());
+        let err = run("javob = birlashtir(\"abc\", \",\")").unwrap_err();
+        assert_eq!(
+            err.kind.to_string(),
+            "'birlashtir' funksiyasining 1-parametri massiv bo'lishi kerak, matn berildi"
+        );
+    }
+
+    #[test]
+    fn test_search_and_trim() {
+        assert_eq!(
+            text(
+                "s = \"  salom dunyo \"\n\
+                 javob = [ichidami(s, \"dun\"), boshlanadimi(s, \"sa\"), tugaydimi(tozala(s), \"yo\")]"
+            ),
+            "[rost; yolg'on; rost]"
+        );
+        assert_eq!(text("javob = tozala(\"\\t matn \\n\")"), "matn");
+    }
+}
@@ -0,0 +1,473 @@
+use crate::compiler::{self, Chunk, Op};
+use crate::diagnostic::Span;
+use crate::interpreter::{
+    DictKey, Function, Interpreter, RuntimeError, RuntimeErrorKind, TraceFrame, Value,
+    assign_index, index_value,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+type EvalResult = Result<Value, RuntimeError>;
+
+/// Bitta funksiya chaqiruvi (yoki dasturning yuqori darajasi) holati.
+struct Frame<'a> {
+    chunk: &'a Chunk,
+    slots: Vec<Option<Value>>,
+    stack: Vec<Value>,
+    // Active `uchun` loops: the items and the next position
+    iterators: Vec<(Rc<Vec<Value>>, usize)>,
+    // Slot values shadowed or defined inside loops, restored when the scope ends
+    saved: Vec<(u32, Option<Value>)>,
+    marks: Vec<usize>,
+    handlers: Vec<Handler>,
+}
+
+/// Faol `urinib ko'r` bloki: xato bo'lsa, kadr blok boshlangandagi holatga qaytadi.
+struct Handler {
+    target: usize,
+    stack: usize,
+    iterators: usize,
+    marks: usize,
+}
+
+impl Frame<'_> {
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("VM stack underflow")
+    }
+
+    fn pop_n(&mut self, n: u32) -> Vec<Value> {
+        let at = self.stack.len() - n as usize;
+        self.stack.split_off(at)
+    }
+
+    /// Tsikl tanasida yangi aniqlangan nom har iteratsiya oxirida o'chiriladi.
+    fn define(&mut self, slot: u32, val: Value) {
+        let old = self.slots[slot as usize].replace(val);
+        if !self.iterators.is_empty() {
+            self.saved.push((slot, old));
+        }
+    }
+
+    fn restore_scope(&mut self) {
+        let mark = self.marks.pop().expect("VM scope mark");
+        while self.saved.len() > mark {
+            let (slot, old) = self.saved.pop().expect("saved slot");
+            self.slots[slot as usize] = old;
+        }
+    }
+
+    /// Xato ushlanganda blok ichida boshlangan tsikllarni yopadi.
+    fn unwind(&mut self, handler: &Handler) {
+        while self.marks.len() > handler.marks {
+            self.restore_scope();
+        }
+        self.iterators.truncate(handler.iterators);
+        self.stack.truncate(handler.stack);
+    }
+
+    fn captured(&self) -> HashMap<Rc<str>, Value> {
+        let mut captured = HashMap::new();
+        for (name, val) in self.chunk.slot_names.iter().zip(&self.slots) {
+            if let Some(val) = val {
+                captured.insert(name.clone(), val.clone());
+            }
+        }
+        captured
+    }
+}
+
+impl Interpreter {
+    /// Kompilyatsiya qilingan dasturni bajaradi. `qaytar` bilan to'xtasa, qiymatini qaytaradi.
+    pub(crate) fn run_program(&mut self, chunk: &Chunk) -> Result<Option<Value>, RuntimeError> {
+        let slots = vec![None; chunk.slot_names.len()];
+        self.run_frame(chunk, slots)
+    }
+
+    fn call_value(&mut self, func: &Rc<Function>, args: Vec<Value>, span: Span) -> EvalResult {
+        let params = &func.decl.params;
+        if params.len() != args.len() {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::ArgumentCount {
+                    name: func.display_name().to_string(),
+                    expected: params.len(),
+                    given: args.len(),
+                },
+                span,
+            ));
+        }
+
+        // Functions created by the tree-walking engine are compiled on first call
+        let proto = match &func.code {
+            Some(proto) => Rc::clone(proto),
+            None => Rc::new(compiler::compile_function(func.name.as_deref(), &func.decl)),
+        };
+
+        let mut slots = vec![None; proto.chunk.slot_names.len()];
+        if let Some(captured) = &func.captured {
+            for (slot, name) in slots.iter_mut().zip(&proto.chunk.slot_names) {
+                *slot = captured.get(name).cloned();
+            }
+            // Mahalliy nomli funksiya o'zini chaqira olishi uchun
+            if let Some(slot) = proto.self_slot {
+                slots[slot as usize] = Some(Value::Function(Rc::clone(func)));
+            }
+        }
+        for (&slot, arg) in proto.params.iter().zip(args) {
+            slots[slot as usize] = Some(arg);
+        }
+
+        self.enter_call(span)?;
+        let result = self.with_stack(|vm| {
+            vm.run_in_module(&func.module, span, |vm| vm.run_frame(&proto.chunk, slots))
+        });
+        self.leave_call();
+        match result {
+            Ok(Some(val)) => Ok(val),
+            Ok(None) => Ok(Value::Number(0)), // Default return 0
+            Err(mut err) => {
+                err.trace.push(TraceFrame {
+                    function: func.display_name().to_string(),
+                    span,
+                });
+                Err(err)
+            }
+        }
+    }
+
+    fn call_callee(
+        &mut self,
+        chunk: &Chunk,
+        callee: Value,
+        args: Vec<Value>,
+        callee_span: u32,
+        arg_spans: u32,
+        span: Span,
+    ) -> EvalResult {
+        match callee {
+            Value::Function(func) => self.call_value(&func, args, span),
+            Value::Native(native) => {
+                let start = arg_spans as usize;
+                let arg_spans = &chunk.extra_spans[start..start + args.len()];
+                self.call_native(&native, args, arg_spans, span)
+            }
+            _ => Err(RuntimeError::type_mismatch(
+                format!(
+                    "{} funksiya emas, uni chaqirib bo'lmaydi",
+                    callee.type_name()
+                ),
+                chunk.extra_spans[callee_span as usize],
+            )),
+        }
+    }
+
+    /// Global o'zgaruvchini, u bo'lmasa shu nomdagi native funksiyani o'qiydi.
+    fn load_global(&mut self, name: &str) -> Option<Value> {
+        match self.globals_mut().get(name) {
+            Some(val) => Some(val.clone()),
+            None => self.natives.get(name).cloned().map(Value::Native),
+        }
+    }
+
+    /// Slotni, u bo'sh bo'lsa global nomni o'qiydi.
+    fn load(&mut self, frame: &Frame, slot: u32) -> Option<Value> {
+        match &frame.slots[slot as usize] {
+            Some(val) => Some(val.clone()),
+            None => self.load_global(&frame.chunk.slot_names[slot as usize]),
+        }
+    }
+
+    fn run_frame(
+        &mut self,
+        chunk: &Chunk,
+        slots: Vec<Option<Value>>,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let mut frame = Frame {
+            chunk,
+            slots,
+            stack: Vec::with_capacity(16),
+            iterators: Vec::new(),
+            saved: Vec::new(),
+            marks: Vec::new(),
+            handlers: Vec::new(),
+        };
+        let mut ip = 0;
+        loop {
+            match self.run_ops(&mut frame, ip) {
+                Err(err) if err.is_catchable() => {
+                    let Some(handler) = frame.handlers.pop() else {
+                        return Err(err);
+                    };
+                    frame.unwind(&handler);
+                    frame.stack.push(err.to_value());
+                    ip = handler.target;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Kadrni `ip` dan boshlab `qaytar` yoki kod oxirigacha bajaradi.
+    fn run_ops(&mut self, frame: &mut Frame, mut ip: usize) -> Result<Option<Value>, RuntimeError> {
+        let chunk = frame.chunk;
+        while let Some(&op) = chunk.code.get(ip) {
+            let span = chunk.spans[ip];
+            ip += 1;
+            match op {
+                Op::Const(idx) => frame.stack.push(chunk.constants[idx as usize].clone()),
+                Op::LoadLocal(slot) => {
+                    let Some(val) = self.load(frame, slot) else {
+                        let name = chunk.slot_names[slot as usize].to_string();
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::UndefinedVariable(name),
+                            span,
+                        ));
+                    };
+                    frame.stack.push(val);
+                }
+                Op::LoadGlobal(name) => {
+                    let name = &chunk.names[name as usize];
+                    let Some(val) = self.load_global(name) else {
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::UndefinedVariable(name.to_string()),
+                            span,
+                        ));
+                    };
+                    frame.stack.push(val);
+                }
+                Op::LoadCalleeLocal(slot) => {
+                    let Some(val) = self.load(frame, slot) else {
+                        let name = chunk.slot_names[slot as usize].to_string();
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::UnknownFunction(name),
+                            span,
+                        ));
+                    };
+                    frame.stack.push(val);
+                }
+                Op::LoadCalleeGlobal(name) => {
+                    let name = &chunk.names[name as usize];
+                    let Some(val) = self.load_global(name) else {
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::UnknownFunction(name.to_string()),
+                            span,
+                        ));
+                    };
+                    frame.stack.push(val);
+                }
+                Op::StoreLocal(slot) => {
+                    let val = frame.pop();
+                    if let Some(existing) = &mut frame.slots[slot as usize] {
+                        *existing = val;
+                    } else if let Some(existing) =
+                        self.globals_mut().get_mut(&chunk.slot_names[slot as usize])
+                    {
+                        *existing = val;
+                    } else {
+                        frame.define(slot, val);
+                    }
+                }
+                Op::StoreGlobal(name) => {
+                    let val = frame.pop();
+                    self.globals_mut()
+                        .insert(chunk.names[name as usize].clone(), val);
+                }
+                Op::DefineLocal(slot) => {
+                    let val = frame.pop();
+                    frame.define(slot, val);
+                }
+                Op::SetIndexLocal { slot, index_span } => {
+                    let value_val = frame.pop();
+                    let index_val = frame.pop();
+                    let name = &chunk.slot_names[slot as usize];
+                    let limits = self.limits;
+                    let target = match &mut frame.slots[slot as usize] {
+                        Some(val) => val,
+                        None => self.globals_mut().get_mut(name).ok_or_else(|| {
+                            RuntimeError::new(
+                                RuntimeErrorKind::UndefinedVariable(name.to_string()),
+                                span,
+                            )
+                        })?,
+                    };
+                    let index_span = chunk.extra_spans[index_span as usize];
+                    assign_index(target, name, index_val, value_val, span, index_span)?;
+                    limits.check_size(target, span)?;
+                }
+                Op::SetIndexGlobal { name, index_span } => {
+                    let value_val = frame.pop();
+                    let index_val = frame.pop();
+                    let name = &chunk.names[name as usize];
+                    let limits = self.limits;
+                    let target = self.globals_mut().get_mut(name).ok_or_else(|| {
+                        RuntimeError::new(
+                            RuntimeErrorKind::UndefinedVariable(name.to_string()),
+                            span,
+                        )
+                    })?;
+                    let index_span = chunk.extra_spans[index_span as usize];
+                    assign_index(target, name, index_val, value_val, span, index_span)?;
+                    limits.check_size(target, span)?;
+                }
+                Op::Index { target_span } => {
+                    let index_val = frame.pop();
+                    let target_val = frame.pop();
+                    let target_span = chunk.extra_spans[target_span as usize];
+                    frame
+                        .stack
+                        .push(index_value(target_val, index_val, target_span, span)?);
+                }
+                Op::Array(len) => {
+                    let values = frame.pop_n(len);
+                    frame.stack.push(Value::Array(Rc::new(values)));
+                }
+                Op::CheckKey => {
+                    let key = frame.stack.last().expect("VM stack underflow");
+                    DictKey::from_value(key, span)?;
+                }
+                Op::Dict(len) => {
+                    let values = frame.pop_n(len * 2);
+                    let mut dict = BTreeMap::new();
+                    let mut values = values.into_iter();
+                    while let (Some(key), Some(val)) = (values.next(), values.next()) {
+                        // Keys were checked by CheckKey as soon as they were evaluated
+                        dict.insert(DictKey::from_value(&key, span)?, val);
+                    }
+                    frame.stack.push(Value::Dict(Rc::new(dict)));
+                }
+                Op::Binary(op) => {
+                    let right = frame.pop();
+                    let left = frame.pop();
+                    frame
+                        .stack
+                        .push(self.evaluate_binary(left, op, right, span)?);
+                }
+                Op::Not => {
+                    let val = frame.pop();
+                    frame.stack.push(Value::Bool(!self.is_truthy(val)));
+                }
+                Op::And(target) => {
+                    let val = frame.pop();
+                    if !self.is_truthy(val) {
+                        frame.stack.push(Value::Bool(false));
+                        ip = target as usize;
+                    }
+                }
+                Op::Or(target) => {
+                    let val = frame.pop();
+                    if self.is_truthy(val) {
+                        frame.stack.push(Value::Bool(true));
+                        ip = target as usize;
+                    }
+                }
+                Op::ToBool => {
+                    let val = frame.pop();
+                    frame.stack.push(Value::Bool(self.is_truthy(val)));
+                }
+                Op::Jump(target) => ip = target as usize,
+                Op::JumpIfFalse(target) => {
+                    let val = frame.pop();
+                    if !self.is_truthy(val) {
+                        ip = target as usize;
+                    }
+                }
+                Op::Print => {
+                    let val = frame.pop();
+                    self.print(&val);
+                }
+                Op::Input => {
+                    let val = self.read_input();
+                    frame.stack.push(val);
+                }
+                Op::Import { path, alias } => {
+                    let alias = alias.map(|alias| &*chunk.names[alias as usize]);
+                    self.import_module(&chunk.names[path as usize], alias, span)?;
+                }
+                Op::Pop => {
+                    frame.pop();
+                }
+                Op::MakeFunction { proto, capture } => {
+                    let proto = &chunk.functions[proto as usize];
+                    let captured = capture.then(|| frame.captured());
+                    frame.stack.push(Value::Function(Rc::new(Function {
+                        name: proto.name.clone(),
+                        decl: Rc::clone(&proto.decl),
+                        captured,
+                        code: Some(Rc::clone(proto)),
+                        module: Rc::clone(&self.modules.current),
+                    })));
+                }
+                Op::Call {
+                    argc,
+                    callee_span,
+                    arg_spans,
+                } => {
+                    let args = frame.pop_n(argc);
+                    let callee = frame.pop();
+                    let val =
+                        self.call_callee(chunk, callee, args, callee_span, arg_spans, span)?;
+                    frame.stack.push(val);
+                }
+                Op::CallNamed {
+                    argc,
+                    callee_span,
+                    arg_spans,
+                } => {
+                    let callee = frame.pop();
+                    let args = frame.pop_n(argc);
+                    let val =
+                        self.call_callee(chunk, callee, args, callee_span, arg_spans, span)?;
+                    frame.stack.push(val);
+                }
+                Op::ForStart(slot) => {
+                    let items = match frame.pop() {
+                        Value::Array(elements) => elements,
+                        // Lug'at bo'yicha tsikl kalitlarni beradi
+                        Value::Dict(dict) => Rc::new(dict.keys().map(DictKey::to_value).collect()),
+                        other => {
+                            return Err(RuntimeError::type_mismatch(
+                                format!(
+                                    "'uchun' faqat massiv va lug'atlar bilan ishlaydi, {} berildi",
+                                    other.type_name()
+                                ),
+                                span,
+                            ));
+                        }
+                    };
+                    frame.iterators.push((items, 0));
+                    frame.marks.push(frame.saved.len());
+                    // The loop variable shadows any outer binding until the loop ends
+                    let old = frame.slots[slot as usize].take();
+                    frame.saved.push((slot, old));
+                }
+                Op::ForNext { slot, exit } => {
+                    let (items, pos) = frame.iterators.last_mut().expect("VM iterator");
+                    if let Some(item) = items.get(*pos) {
+                        self.step(span)?;
+                        frame.slots[slot as usize] = Some(item.clone());
+                        *pos += 1;
+                        frame.marks.push(frame.saved.len());
+                    } else {
+                        ip = exit as usize;
+                    }
+                }
+                Op::EndIteration => frame.restore_scope(),
+                Op::ForEnd => {
+                    frame.restore_scope();
+                    frame.iterators.pop();
+                }
+                Op::Step => self.step(span)?,
+                Op::TryStart(target) => frame.handlers.push(Handler {
+                    target: target as usize,
+                    stack: frame.stack.len(),
+                    iterators: frame.iterators.len(),
+                    marks: frame.marks.len(),
+                }),
+                Op::TryEnd => {
+                    frame.handlers.pop();
+                }
+                Op::Return => return Ok(Some(frame.pop())),
+            }
+        }
+        Ok(None)
+    }
+}